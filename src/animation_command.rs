@@ -0,0 +1,117 @@
+use pi_curves::curve::{frame::KeyFrameCurveValue, FramePerSecond};
+
+use crate::{
+    amount::AnimationAmountCalc,
+    animation_group::AnimationGroupID,
+    base::{EFillMode, TimeMS},
+    loop_mode::ELoopMode,
+};
+
+/// 动画组的启动参数 - start_complete 与 start_with_progress 共用
+/// * `loop_mode` 循环模式
+/// * `frame_per_second` 指定动画组每秒运行多少帧
+/// * `amount_calc` 播放进度变化控制
+/// * `delay_time_ms` 延时 - 负值时 从播放途中开始
+pub struct AnimationStartParam {
+    pub loop_mode: ELoopMode,
+    pub frame_per_second: FramePerSecond,
+    pub amount_calc: AnimationAmountCalc,
+    pub delay_time_ms: TimeMS,
+    pub fillmode: EFillMode,
+}
+
+impl AnimationStartParam {
+    pub fn new(
+        loop_mode: ELoopMode,
+        frame_per_second: FramePerSecond,
+        amount_calc: AnimationAmountCalc,
+        delay_time_ms: TimeMS,
+        fillmode: EFillMode,
+    ) -> Self {
+        Self { loop_mode, frame_per_second, amount_calc, delay_time_ms, fillmode }
+    }
+}
+
+/// 事件回调中 对动画组的操作 - 在事件派发结束后 由 AnimationContextAmount 按顺序执行
+pub enum EAnimationCommand {
+    /// 参见 AnimationContextAmount.start_complete
+    StartComplete {
+        group: AnimationGroupID,
+        seconds: KeyFrameCurveValue,
+        param: AnimationStartParam,
+    },
+    /// 参见 AnimationContextAmount.start_with_progress
+    StartWithProgress {
+        group: AnimationGroupID,
+        speed: KeyFrameCurveValue,
+        from: KeyFrameCurveValue,
+        to: KeyFrameCurveValue,
+        param: AnimationStartParam,
+    },
+    Pause(AnimationGroupID),
+    Stop(AnimationGroupID),
+    /// 参见 AnimationContextAmount.seek
+    Seek(AnimationGroupID, KeyFrameCurveValue),
+}
+
+impl EAnimationCommand {
+    /// 操作的动画组
+    pub fn group(&self) -> AnimationGroupID {
+        match self {
+            EAnimationCommand::StartComplete { group, .. } => *group,
+            EAnimationCommand::StartWithProgress { group, .. } => *group,
+            EAnimationCommand::Pause(group) => *group,
+            EAnimationCommand::Stop(group) => *group,
+            EAnimationCommand::Seek(group, _) => *group,
+        }
+    }
+}
+
+/// 动画操作队列 - 事件回调通过它安全地修改动画状态
+#[derive(Default)]
+pub struct AnimationCommands {
+    list: Vec<EAnimationCommand>,
+}
+
+impl AnimationCommands {
+    pub fn push(&mut self, command: EAnimationCommand) {
+        self.list.push(command);
+    }
+    pub fn start_complete(
+        &mut self,
+        group: AnimationGroupID,
+        seconds: KeyFrameCurveValue,
+        param: AnimationStartParam,
+    ) {
+        self.list.push(EAnimationCommand::StartComplete { group, seconds, param });
+    }
+    pub fn start_with_progress(
+        &mut self,
+        group: AnimationGroupID,
+        speed: KeyFrameCurveValue,
+        from: KeyFrameCurveValue,
+        to: KeyFrameCurveValue,
+        param: AnimationStartParam,
+    ) {
+        self.list.push(EAnimationCommand::StartWithProgress { group, speed, from, to, param });
+    }
+    pub fn pause(&mut self, group: AnimationGroupID) {
+        self.list.push(EAnimationCommand::Pause(group));
+    }
+    pub fn stop(&mut self, group: AnimationGroupID) {
+        self.list.push(EAnimationCommand::Stop(group));
+    }
+    pub fn seek(&mut self, group: AnimationGroupID, progress: KeyFrameCurveValue) {
+        self.list.push(EAnimationCommand::Seek(group, progress));
+    }
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+    /// 取出所有操作
+    pub fn drain(&mut self) -> std::vec::Drain<'_, EAnimationCommand> {
+        self.list.drain(..)
+    }
+}
@@ -107,7 +107,7 @@ impl<T: Clone + PartialEq + Eq + PartialOrd + Ord, M: AnimationGroupManager<T>>
     ) -> Result<(), EAnimationError> {
         match self.group_mgr.get_mut(group_id) {
             Some(group) => {
-                group.add_target_animation(TargetAnimation { target, animation })
+                group.add_target_animation(TargetAnimation::new(target, animation))
             },
             None => Err(EAnimationError::AnimationGroupNotFound),
         }
@@ -0,0 +1,273 @@
+use pi_hash::XHashMap;
+use pi_null::Null;
+use pi_slotmap::{SlotMap, SecondaryMap, DefaultKey};
+
+use crate::{
+    animation_command::AnimationCommands,
+    animation_group::{AnimationGroupID, AnimationGroupRuntimeInfo},
+    animation_listener::{AnimationListener, AnimationEvent, EAnimationEvent, EAnimationEventResult, AnimationEventPayload, OnAnimationEvent, EListenerErrorPolicy},
+    error::EAnimationError,
+    curve_frame_event::FrameEventData,
+};
+
+pub type AnimationListenerID = DefaultKey;
+
+/// 注册到派发器的监听
+enum EListener {
+    /// 按事件类型分别回调的监听器
+    Listener(Box<AnimationListener<FrameEventData>>),
+    /// 接收事件参数的回调 - (监听的动画组, 回调)
+    Handler(AnimationGroupID, OnAnimationEvent),
+}
+
+impl EListener {
+    fn group(&self) -> AnimationGroupID {
+        match self {
+            EListener::Listener(listener) => listener.group,
+            EListener::Handler(group, _) => *group,
+        }
+    }
+}
+
+/// 动画事件派发器
+/// * 监听器注册到指定动画组, 或 通过 AnimationListener::global 注册为全局监听器
+/// * 每个事件 先派发给该动画组的监听, 再派发给全局监听, 各自按注册顺序
+/// * 同一动画组 连续的帧事件 对 AnimationListener 合并为一次 on_frame 调用, 对回调则逐个派发
+/// * 回调返回错误时 按各监听的 EListenerErrorPolicy 处理, 默认移除出错的回调
+#[derive(Default)]
+pub struct AnimationEventDispatcher {
+    listeners: SlotMap<DefaultKey, EListener>,
+    policies: SecondaryMap<DefaultKey, EListenerErrorPolicy>,
+    group_listeners: XHashMap<AnimationGroupID, Vec<AnimationListenerID>>,
+    global_listeners: Vec<AnimationListenerID>,
+}
+
+impl AnimationEventDispatcher {
+    /// 注册监听器 - 返回用于移除的句柄
+    pub fn add(&mut self, listener: AnimationListener<FrameEventData>) -> AnimationListenerID {
+        self.insert(EListener::Listener(Box::new(listener)))
+    }
+    /// 注册回调 - 返回用于移除的句柄
+    /// * `group` 为 null 时监听所有动画组
+    pub fn add_handler(&mut self, group: AnimationGroupID, call: OnAnimationEvent) -> AnimationListenerID {
+        self.insert(EListener::Handler(group, call))
+    }
+    fn insert(&mut self, listener: EListener) -> AnimationListenerID {
+        let group = listener.group();
+        let id = self.listeners.insert(listener);
+        self.policies.insert(id, EListenerErrorPolicy::default());
+        if group.is_null() {
+            self.global_listeners.push(id);
+        } else {
+            match self.group_listeners.get_mut(&group) {
+                Some(list) => list.push(id),
+                None => {
+                    self.group_listeners.insert(group, vec![id]);
+                },
+            }
+        }
+        id
+    }
+    /// 移除监听器 或 回调
+    /// * 返回 句柄是否有效
+    pub fn remove(&mut self, id: AnimationListenerID) -> bool {
+        match self.listeners.remove(id) {
+            Some(listener) => {
+                self.policies.remove(id);
+                let group = listener.group();
+                if group.is_null() {
+                    self.global_listeners.retain(|item| *item != id);
+                } else {
+                    let mut empty = false;
+                    if let Some(list) = self.group_listeners.get_mut(&group) {
+                        list.retain(|item| *item != id);
+                        empty = list.is_empty();
+                    }
+                    if empty {
+                        self.group_listeners.remove(&group);
+                    }
+                }
+                true
+            },
+            None => false,
+        }
+    }
+    /// 移除 注册到动画组的所有监听器 - 动画组删除时调用
+    pub fn remove_group(&mut self, group: AnimationGroupID) {
+        match self.group_listeners.remove(&group) {
+            Some(list) => list.iter().for_each(|id| {
+                self.listeners.remove(*id);
+                self.policies.remove(*id);
+            }),
+            None => {},
+        }
+    }
+    /// 设置监听 回调出错时的处理方式
+    /// * 返回 句柄是否有效
+    pub fn set_error_policy(&mut self, id: AnimationListenerID, policy: EListenerErrorPolicy) -> bool {
+        match self.policies.get_mut(id) {
+            Some(item) => {
+                *item = policy;
+                true
+            },
+            None => false,
+        }
+    }
+    /// 获取监听器 - 回调句柄返回 None
+    pub fn get_mut(&mut self, id: AnimationListenerID) -> Option<&mut AnimationListener<FrameEventData>> {
+        match self.listeners.get_mut(id) {
+            Some(EListener::Listener(listener)) => Some(listener.as_mut()),
+            _ => None,
+        }
+    }
+    /// 已注册的监听器数目
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+    /// 按顺序派发事件
+    /// * `events` 动画组事件列表 - 参见 AnimationContextAmount.events
+    /// * `group_infos` 用于填充回调参数
+    /// * `commands` 回调提交的操作
+    /// * 返回 策略为 Propagate 的监听产生的错误 - (动画组, 事件类型, 错误)
+    pub fn dispatch(
+        &mut self,
+        events: &[AnimationEvent],
+        group_infos: &SecondaryMap<DefaultKey, AnimationGroupRuntimeInfo>,
+        commands: &mut AnimationCommands,
+    ) -> Vec<(AnimationGroupID, EAnimationEvent, EAnimationError)> {
+        let mut errors = vec![];
+        if self.listeners.is_empty() {
+            return errors;
+        }
+
+        let looped_counts = Self::event_looped_counts(events, group_infos);
+        let mut index = 0;
+        while index < events.len() {
+            let (group, ty) = (events[index].group(), events[index].kind());
+            let start = index;
+            if ty == EAnimationEvent::FrameEvent {
+                while index < events.len() && events[index].group() == group && events[index].kind() == EAnimationEvent::FrameEvent {
+                    index += 1;
+                }
+            } else {
+                index += 1;
+            }
+            let batch = &events[start..index];
+
+            let mut ids = match self.group_listeners.get(&group) {
+                Some(list) => list.clone(),
+                None => vec![],
+            };
+            ids.extend_from_slice(&self.global_listeners);
+            for id in ids {
+                let policy = match self.policies.get(id) {
+                    Some(policy) => *policy,
+                    None => EListenerErrorPolicy::default(),
+                };
+                let (remove, mut call_errors) = match self.listeners.get_mut(id) {
+                    Some(EListener::Listener(listener)) => {
+                        (false, Self::call_listener(listener, batch, policy))
+                    },
+                    Some(EListener::Handler(_, call)) => {
+                        let mut remove = false;
+                        let mut call_errors = vec![];
+                        for (offset, event) in batch.iter().enumerate() {
+                            let payload = Self::payload(event, looped_counts[start + offset], group_infos);
+                            match call(&payload, commands) {
+                                Ok(EAnimationEventResult::None) => {},
+                                Ok(EAnimationEventResult::RemoveListen) => {
+                                    remove = true;
+                                },
+                                Err(e) => {
+                                    call_errors.push(e);
+                                    remove = policy == EListenerErrorPolicy::Remove;
+                                },
+                            }
+                            if remove {
+                                break;
+                            }
+                        }
+                        (remove, call_errors)
+                    },
+                    None => (false, vec![]),
+                };
+                call_errors.drain(..).for_each(|e| {
+                    match policy {
+                        EListenerErrorPolicy::Propagate => errors.push((group, ty, e)),
+                        _ => log::warn!("animation listener error: group {:?}, event {:?}, error {:?}", group, ty, e),
+                    }
+                });
+                if remove {
+                    self.remove(id);
+                }
+            }
+        }
+
+        errors
+    }
+    fn call_listener(
+        listener: &mut AnimationListener<FrameEventData>,
+        batch: &[AnimationEvent],
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        match batch[0] {
+            AnimationEvent::Start(_) => listener.on_start_with(policy),
+            AnimationEvent::End(_) => listener.on_end_with(policy),
+            AnimationEvent::Loop(_, count) => listener.on_loop_with(count, policy),
+            AnimationEvent::FrameEvent(_, _) => listener.on_frame_with(batch.iter().map(|item| item.param() as FrameEventData).collect(), policy),
+            AnimationEvent::Pause(_, _) => listener.on_pause_with(policy),
+            AnimationEvent::Stop(_, _) => listener.on_stop_with(policy),
+            AnimationEvent::RangeEnter(_, data) => listener.on_range_enter_with(data, policy),
+            AnimationEvent::RangeExit(_, data) => listener.on_range_exit_with(data, policy),
+            AnimationEvent::Cancel(_, reason, _) => listener.on_cancel_with(reason, policy),
+            AnimationEvent::Remove(_, _) => listener.on_remove_with(policy),
+            AnimationEvent::DelayEnd(_) => listener.on_delay_end_with(policy),
+            AnimationEvent::IterationStart(_, index) => listener.on_iteration_start_with(index, policy),
+        }
+    }
+    /// 各事件发生时 动画组已完成的循环次数
+    /// * 从本次计算开始时的循环次数起 依次经过 Loop 事件
+    fn event_looped_counts(
+        events: &[AnimationEvent],
+        group_infos: &SecondaryMap<DefaultKey, AnimationGroupRuntimeInfo>,
+    ) -> Vec<u32> {
+        let mut counts: XHashMap<AnimationGroupID, u32> = XHashMap::default();
+        events.iter().map(|event| {
+            let group = event.group();
+            let count = counts.entry(group).or_insert_with(|| match group_infos.get(group) {
+                Some(info) => info.last_looped_count,
+                None => 0,
+            });
+            if let AnimationEvent::Loop(_, looped_count) = event {
+                *count = *looped_count;
+            }
+            *count
+        }).collect()
+    }
+    /// 事件回调参数
+    /// * `looped_count` 事件发生时 已完成的循环次数, 用于 本次计算推进中产生的事件
+    fn payload(
+        event: &AnimationEvent,
+        looped_count: u32,
+        group_infos: &SecondaryMap<DefaultKey, AnimationGroupRuntimeInfo>,
+    ) -> AnimationEventPayload {
+        let running_looped_count = looped_count;
+        let group = event.group();
+        let (looped_count, amount_in_second, running_time_ms) = match (event.position(), group_infos.get(group)) {
+            (Some(position), _) => (position.looped_count, position.amount_in_second, position.running_time_ms),
+            (None, Some(info)) => (info.looped_count, info.amount_in_second, info.running_time_ms),
+            (None, None) => (0, 0., 0.),
+        };
+        let (looped_count, data) = match event {
+            AnimationEvent::Loop(_, count) => (*count, 0),
+            AnimationEvent::IterationStart(_, index) => (*index, 0),
+            AnimationEvent::FrameEvent(_, data) | AnimationEvent::RangeEnter(_, data) | AnimationEvent::RangeExit(_, data) => (running_looped_count, *data),
+            AnimationEvent::Start(_) | AnimationEvent::DelayEnd(_) => (running_looped_count, 0),
+            _ => (looped_count, 0),
+        };
+        AnimationEventPayload { group, event: event.kind(), looped_count, amount_in_second, running_time_ms, data }
+    }
+}
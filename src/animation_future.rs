@@ -0,0 +1,215 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use pi_slotmap::{DefaultKey, SecondaryMap};
+
+pub use crate::animation_listener::EAnimationEndReason;
+
+use crate::{
+    animation_group::{AnimationGroupID, AnimationGroupRuntimeInfo},
+    animation_listener::AnimationEvent,
+    curve_frame_event::FrameEventData,
+};
+
+struct WaitState<R> {
+    result: Option<R>,
+    waker: Option<Waker>,
+}
+
+type SharedWaitState<R> = Arc<Mutex<WaitState<R>>>;
+/// 等待动画组进度的记录 - (动画组, 等待条件, 等待状态)
+type ProgressWaiter = (AnimationGroupID, EWaitFor, SharedWaitState<Result<(), EAnimationEndReason>>);
+
+fn resolve<R>(state: &SharedWaitState<R>, result: R) {
+    let mut state = state.lock().unwrap();
+    state.result = Some(result);
+    if let Some(waker) = state.waker.take() {
+        waker.wake();
+    }
+}
+
+/// 等待动画状态的 Future - 不依赖任何异步运行时
+/// * 由 AnimationContextAmount 的 finished looped frame_event_reached 创建
+/// * 在 anime_curve_calc 产生对应事件时唤醒
+pub struct AnimationWait<R> {
+    state: SharedWaitState<R>,
+}
+
+impl<R> AnimationWait<R> {
+    fn new() -> (Self, SharedWaitState<R>) {
+        let state = Arc::new(Mutex::new(WaitState { result: None, waker: None }));
+        (Self { state: state.clone() }, state)
+    }
+    /// 已有结果 - 首次 poll 即完成
+    fn ready(result: R) -> Self {
+        Self { state: Arc::new(Mutex::new(WaitState { result: Some(result), waker: None })) }
+    }
+}
+
+impl<R> Future for AnimationWait<R> {
+    type Output = R;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            },
+        }
+    }
+}
+
+/// 动画组进度的等待条件
+enum EWaitFor {
+    /// 循环次数达到
+    Loop(u32),
+    /// 帧事件触发
+    FrameEvent(FrameEventData),
+}
+
+/// 动画等待记录 - 依据动画事件 唤醒 AnimationWait
+#[derive(Default)]
+pub struct AnimationWaiters {
+    finished: Vec<(AnimationGroupID, SharedWaitState<EAnimationEndReason>)>,
+    progress: Vec<ProgressWaiter>,
+    /// 各动画组最近一次结束的原因
+    end_reasons: SecondaryMap<DefaultKey, EAnimationEndReason>,
+}
+
+impl AnimationWaiters {
+    /// 等待动画组结束
+    /// * 动画组未在播放时 立即完成, 结果为最近一次结束的原因, 从未结束过则为 Stopped
+    pub fn finished(&mut self, group: AnimationGroupID, is_playing: bool) -> AnimationWait<EAnimationEndReason> {
+        if !is_playing {
+            return AnimationWait::ready(self.end_reason(group));
+        }
+        let (wait, state) = AnimationWait::new();
+        self.finished.push((group, state));
+        wait
+    }
+    /// 等待动画组循环次数达到 `count`
+    /// * 已达到时 立即完成
+    /// * 动画组先结束 或 未在播放 则返回 Err(结束原因)
+    pub fn looped(&mut self, group: AnimationGroupID, count: u32, is_playing: bool, looped_count: u32) -> AnimationWait<Result<(), EAnimationEndReason>> {
+        if looped_count >= count {
+            return AnimationWait::ready(Ok(()));
+        }
+        if !is_playing {
+            return AnimationWait::ready(Err(self.end_reason(group)));
+        }
+        let (wait, state) = AnimationWait::new();
+        self.progress.push((group, EWaitFor::Loop(count), state));
+        wait
+    }
+    /// 等待动画组触发携带 `data` 的帧事件
+    /// * 动画组先结束 或 未在播放 则返回 Err(结束原因)
+    pub fn frame_event_reached(&mut self, group: AnimationGroupID, data: FrameEventData, is_playing: bool) -> AnimationWait<Result<(), EAnimationEndReason>> {
+        if !is_playing {
+            return AnimationWait::ready(Err(self.end_reason(group)));
+        }
+        let (wait, state) = AnimationWait::new();
+        self.progress.push((group, EWaitFor::FrameEvent(data), state));
+        wait
+    }
+    pub fn is_empty(&self) -> bool {
+        self.finished.is_empty() && self.progress.is_empty()
+    }
+    /// 动画组最近一次结束的原因 - 从未结束过则为 Stopped
+    pub fn end_reason(&self, group: AnimationGroupID) -> EAnimationEndReason {
+        match self.end_reasons.get(group) {
+            Some(reason) => *reason,
+            None => EAnimationEndReason::Stopped,
+        }
+    }
+    /// 记录动画组结束的原因 - 停止时 事件在下次计算才产生, 需立即记录
+    pub fn record_end(&mut self, group: AnimationGroupID, reason: EAnimationEndReason) {
+        self.end_reasons.insert(group, reason);
+    }
+    /// 依据本次计算的事件 唤醒等待
+    pub fn notify(
+        &mut self,
+        events: &[AnimationEvent],
+        group_infos: &SecondaryMap<DefaultKey, AnimationGroupRuntimeInfo>,
+    ) {
+        for event in events.iter() {
+            match event {
+                AnimationEvent::End(group) => self.record_end(*group, EAnimationEndReason::Completed),
+                AnimationEvent::Stop(group, _) => self.record_end(*group, EAnimationEndReason::Stopped),
+                _ => {},
+            }
+        }
+        if self.is_empty() {
+            return;
+        }
+        for event in events.iter() {
+            match event {
+                AnimationEvent::End(group) => {
+                    let looped_count = match group_infos.get(*group) {
+                        Some(info) => info.looped_count,
+                        None => 0,
+                    };
+                    self.end(*group, EAnimationEndReason::Completed, looped_count);
+                },
+                AnimationEvent::Stop(group, _) => self.end(*group, EAnimationEndReason::Stopped, 0),
+                AnimationEvent::Loop(group, looped_count) => {
+                    self.progress.retain(|(id, wait_for, state)| {
+                        match wait_for {
+                            EWaitFor::Loop(count) if *id == *group && *count <= *looped_count => {
+                                resolve(state, Ok(()));
+                                false
+                            },
+                            _ => true,
+                        }
+                    });
+                },
+                AnimationEvent::FrameEvent(group, data) => {
+                    self.progress.retain(|(id, wait_for, state)| {
+                        match wait_for {
+                            EWaitFor::FrameEvent(wait_data) if *id == *group && *wait_data == *data => {
+                                resolve(state, Ok(()));
+                                false
+                            },
+                            _ => true,
+                        }
+                    });
+                },
+                _ => {},
+            }
+        }
+        // 不再被等待的记录
+        self.finished.retain(|(_, state)| Arc::strong_count(state) > 1);
+        self.progress.retain(|(_, _, state)| Arc::strong_count(state) > 1);
+    }
+    /// 动画组被删除 - 唤醒其所有等待
+    pub fn remove_group(&mut self, group: AnimationGroupID) {
+        self.end_reasons.remove(group);
+        self.end(group, EAnimationEndReason::Removed, 0);
+    }
+    /// 动画组结束 - 完成时 循环次数未超过 `looped_count` 的等待视为达到
+    fn end(&mut self, group: AnimationGroupID, reason: EAnimationEndReason, looped_count: u32) {
+        self.finished.retain(|(id, state)| {
+            if *id == group {
+                resolve(state, reason);
+                false
+            } else {
+                true
+            }
+        });
+        self.progress.retain(|(id, wait_for, state)| {
+            if *id == group {
+                match wait_for {
+                    EWaitFor::Loop(count) if reason == EAnimationEndReason::Completed && *count <= looped_count => resolve(state, Ok(())),
+                    _ => resolve(state, Err(reason)),
+                }
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
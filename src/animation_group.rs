@@ -1,11 +1,11 @@
-use std::{ops::Deref, hash::Hash, sync::Arc};
+use std::{ops::Deref, hash::Hash, sync::Arc, mem::replace};
 
-use pi_curves::curve::{frame::KeyFrameCurveValue, FramePerSecond};
+use pi_curves::curve::{frame::{KeyFrameCurveValue, KeyFrameDataType}, FramePerSecond};
 use pi_slotmap::DefaultKey;
 use pi_null::Null;
 
 
-use crate::{error::EAnimationError, loop_mode::{ELoopMode, get_amount_calc}, target_modifier::{TAnimatableTargetModifier, IDAnimatableAttr}, runtime_info::{RuntimeInfo, RuntimeInfoMap}, target_animation::TargetAnimation, amount::AnimationAmountCalc, base::{EFillMode, TimeMS}, animation::AnimationInfo};
+use crate::{error::EAnimationError, loop_mode::{ELoopMode, get_amount_passes, AmountPass, IterationRange, IterationCount, TLoopMode}, target_modifier::{TAnimatableTargetModifier, IDAnimatableAttr}, runtime_info::{RuntimeInfo, RuntimeInfoMap}, target_animation::TargetAnimation, amount::AnimationAmountCalc, base::{EFillMode, TimeMS, ETimeStepMode, ELargeDeltaPolicy, IterationDelay, EIterationHold, SeededRandom}, animation::AnimationInfo, frame_curve_manager::{FrameCurveInfo, FrameCurveInfoID}, curve_frame_event::{TimeFrameEvents, FrameEventData, EFrameEventCatchUp}, animation_listener::{EAnimationEvent, AnimationPosition}, time_channel::TimeChannelID, speed_curve::SpeedCurve};
 
 pub type AnimationGroupID = DefaultKey;
 
@@ -15,8 +15,12 @@ pub struct AnimationGroupRuntimeInfo {
     pub last_amount_in_second: KeyFrameCurveValue,
     /// 在 秒 级比例下的进度
     pub amount_in_second: KeyFrameCurveValue,
+    /// 上次计算时的循环次数
+    pub last_looped_count: u32,
     /// 循环次数
     pub looped_count: u32,
+    /// 本次计算跨过的循环次数 - 每次对应一个 Loop 事件
+    pub loop_crossed: u32,
     /// 是否活动状态
     pub is_playing: bool,
     /// 是否触发 loop 事件
@@ -25,6 +29,51 @@ pub struct AnimationGroupRuntimeInfo {
     pub start_event: bool,
     /// 是否触发 end 事件
     pub end_event: bool,
+    /// 是否触发 延时结束 事件
+    pub delay_end_event: bool,
+    /// 本次计算是否屏蔽 帧事件 和 循环事件 - 参见 ELargeDeltaPolicy::Skip
+    pub suppress_event: bool,
+    /// 上次计算时 动画组的有效运行时间
+    pub last_running_time_ms: TimeMS,
+    /// 动画组的有效运行时间 - 与 amount_in_second 对应
+    pub running_time_ms: TimeMS,
+    /// 动画组运行一次的时间 - 进度计算实际使用的值
+    pub once_time_ms: TimeMS,
+    /// 动画组循环模式
+    pub loop_mode: ELoopMode,
+    /// 当前播放是否正向
+    pub positive_pass: bool,
+    /// 动画组循环范围 - 已补全循环次数
+    pub iteration: IterationRange,
+}
+
+impl AnimationGroupRuntimeInfo {
+    /// 本次计算中 动画组经过的各次播放的进度变化, 按时间先后排列
+    /// * 用于查询帧事件, 参见 CurveFrameEvent.query_passes
+    /// * 按 loop_mode 计算, 使用自定义循环模式时 参见 AnimationGroup.amount_passes
+    pub fn amount_passes(&self) -> Vec<AmountPass> {
+        get_amount_passes(&self.loop_mode, &self.iteration, self.once_time_ms, self.last_running_time_ms, self.running_time_ms, self.start_event)
+    }
+    /// 开始一次计算 - 清除事件标记, 记录上次计算的状态
+    pub fn begin_calc(&mut self) {
+        self.start_event = false;
+        self.end_event = false;
+        self.loop_event = false;
+        self.delay_end_event = false;
+        self.suppress_event = false;
+        self.loop_crossed = 0;
+        self.last_amount_in_second = self.amount_in_second;
+        self.last_running_time_ms = self.running_time_ms;
+        self.last_looped_count = self.looped_count;
+    }
+    /// 当前播放位置
+    pub fn position(&self) -> AnimationPosition {
+        AnimationPosition {
+            looped_count: self.looped_count,
+            amount_in_second: self.amount_in_second,
+            running_time_ms: self.running_time_ms,
+        }
+    }
 }
 
 /// 动画组数据结构
@@ -35,7 +84,11 @@ pub struct AnimationGroup<T: Clone + PartialEq + Eq + Hash> {
     // animatable_target_id: T,
     id: AnimationGroupID,
     animations: Vec<TargetAnimation<T>>,
-    loop_count: Option<u32>,
+    /// 循环范围 - 循环起始位置 和 可为小数的循环次数
+    /// * 需要在启动动画前修改
+    pub iteration: IterationRange,
+    /// 启动时 以循环模式补全的循环范围
+    iteration_range: IterationRange,
     /// 动画组速度
     pub speed: KeyFrameCurveValue,
     pub fill_mode: EFillMode,
@@ -47,6 +100,10 @@ pub struct AnimationGroup<T: Clone + PartialEq + Eq + Hash> {
     running_delay_time_ms: TimeMS,
     /// 动画组有效运行时间
     running_time_ms: TimeMS,
+    /// 延时结束后经过的时间 - 未乘速度
+    elapsed_ms: TimeMS,
+    /// 播放速度曲线
+    speed_curve: Option<SpeedCurve>,
     /// 动画组循环记录
     looped_count: u32,
     /// 动画组循环模式
@@ -66,11 +123,43 @@ pub struct AnimationGroup<T: Clone + PartialEq + Eq + Hash> {
     pub(crate) blend_weight: f32,
     /// 动画组的在秒单位下的进度
     amount_in_second: KeyFrameCurveValue,
-    amount: fn(KeyFrameCurveValue, KeyFrameCurveValue) -> (KeyFrameCurveValue, u32),
+    /// 自定义循环模式 - None 时使用 loop_mode
+    loop_calc: Option<Arc<dyn TLoopMode>>,
     /// 动画组整体 进度曲线
     amount_calc: AnimationAmountCalc,
     /// 关键帧之间 进度曲线
     amount_calc_between_frame: Arc<AnimationAmountCalc>,
+    /// 挂载在动画组上的帧事件
+    frame_events: TimeFrameEvents,
+    /// 当前处于其中的区间事件
+    active_ranges: Vec<FrameEventData>,
+    /// 一次计算跨过多次播放时 帧事件的补发方式
+    pub frame_event_catch_up: EFrameEventCatchUp,
+    /// 推进时间的方式
+    pub time_step: ETimeStepMode,
+    /// 大间隔时间的处理方式 - None 时使用所在 context 的设置
+    pub large_delta: Option<ELargeDeltaPolicy>,
+    /// 所属的时间通道 - None 时只受 context 的 time_scale 控制
+    pub time_channel: Option<TimeChannelID>,
+    /// 结束延时 - ms, 播放完成后 保持最终状态的时间, 之后才触发结束事件
+    /// * 需要在启动动画前修改
+    pub end_delay_ms: TimeMS,
+    /// 负延时 跳过的时间 - ms
+    start_offset_ms: TimeMS,
+    /// 进入结束延时时 的经过时间 - ms, None 表示未处于结束延时
+    end_delay_from_ms: Option<TimeMS>,
+    /// 循环之间的停顿
+    iteration_delay: Option<IterationDelay>,
+    /// 当前循环停顿 (开始时的经过时间, 停顿时长) - ms
+    iteration_pause: Option<(TimeMS, TimeMS)>,
+    /// 已完成的循环停顿 总时长 - ms
+    paused_total_ms: TimeMS,
+    /// 是否已触发启动
+    started: bool,
+    /// 绝对时钟驱动时 动画组的启动时刻 - ms
+    start_clock_ms: Option<TimeMS>,
+    /// 绝对时钟驱动时 动画组上次计算的时刻 - ms
+    last_clock_ms: Option<TimeMS>,
     /// 是否为测试模式
     pub debug: bool,
 }
@@ -86,13 +175,16 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
             // animatable_target_id,
             id: AnimationGroupID::null(),
             animations: vec![],
-            loop_count: Some(1),
+            iteration: IterationRange::default(),
+            iteration_range: IterationRange::new(0., Some(1.)),
             speed: 1.,
             from: 0.,
             to: 1.,
             delay_time_ms: 0.,
             running_delay_time_ms: 0.,
             running_time_ms: 0.,
+            elapsed_ms: 0.,
+            speed_curve: None,
             looped_count: 0,
             loop_mode: ELoopMode::Not,
             frame_ms: 16.6,
@@ -104,9 +196,24 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
             blend_weight: 1.0,
             amount_in_second: 0.,
             fill_mode: EFillMode::NONE,
-            amount: get_amount_calc(ELoopMode::Not),
+            loop_calc: None,
             amount_calc: AnimationAmountCalc::default(),
             amount_calc_between_frame: Arc::new(AnimationAmountCalc::default()),
+            frame_events: TimeFrameEvents::default(),
+            frame_event_catch_up: EFrameEventCatchUp::default(),
+            time_step: ETimeStepMode::default(),
+            large_delta: None,
+            time_channel: None,
+            end_delay_ms: 0.,
+            start_offset_ms: 0.,
+            end_delay_from_ms: None,
+            iteration_delay: None,
+            iteration_pause: None,
+            paused_total_ms: 0.,
+            started: false,
+            start_clock_ms: None,
+            last_clock_ms: None,
+            active_ranges: vec![],
             debug: false,
         }
     }
@@ -151,12 +258,43 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
     pub fn anime(
         &mut self,
         runtime_infos: &mut RuntimeInfoMap<T>,
-        delta_ms: KeyFrameCurveValue,
+        delta_ms: TimeMS,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        let is_playing = self.is_playing;
+        self.advance(delta_ms, group_info);
+        if is_playing {
+            self.update_to_infos(runtime_infos);
+        }
+    }
+    /// 推进动画组时间 - 不更新曲线计算信息
+    /// * 用于一次计算中分多步推进, 最后调用 update_to_infos
+    pub fn advance(
+        &mut self,
+        delta_ms: TimeMS,
         group_info: &mut AnimationGroupRuntimeInfo,
     ) {
         group_info.last_amount_in_second = group_info.amount_in_second;
 
-        if self.is_playing {
+        let mut delta_ms = delta_ms;
+        let mut resumed = false;
+        if self.is_playing && self.iteration_pause.is_some() {
+            // 循环停顿中 只推进时间, 停顿结束后 剩余时间已计入运行时间
+            self.elapsed_ms += delta_ms;
+            if !self.iteration_pause_update(group_info) {
+                return;
+            }
+            delta_ms = 0.;
+            resumed = true;
+        }
+
+        if self.is_playing && self.end_delay_from_ms.is_some() {
+            // 结束延时中 只推进时间
+            self.elapsed_ms += delta_ms;
+            self.end_delay_update(false, group_info);
+        } else if self.is_playing && self.time_step.is_deterministic() {
+            self.anime_step(delta_ms, group_info);
+        } else if self.is_playing {
             // // 延时未结束
             // if self.delay_time_ms - self.running_delay_time_ms > self.frame_ms * 0.75 {
             //     self.running_delay_time_ms += delta_ms;
@@ -234,26 +372,514 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
             // }
 
             
-            let mut _is_playing = self.is_playing;
-            let mut _running_delay_time_ms = self.running_delay_time_ms;
-            let mut _running_time_ms = self.running_time_ms;
-            let mut _looped_count = self.looped_count;
-            let mut _amount_in_second = self.amount_in_second;
-            let mut _detal_ms_record = self.detal_ms_record;
-
-            _anime(
-                &mut _is_playing, self.delay_time_ms, &mut _running_delay_time_ms, self.frame_ms, self.loop_mode, self.once_time_ms, self.from,
-                &mut _running_time_ms, &mut _looped_count, &self.amount_calc, self.speed, self.fill_mode, self.debug,
-                &self.amount, &self.loop_count, &mut _amount_in_second, &mut _detal_ms_record, delta_ms, group_info
-            );
-
-            self.is_playing = _is_playing;
-            self.running_delay_time_ms = _running_delay_time_ms;
-            self.running_time_ms = _running_time_ms;
-            self.looped_count = _looped_count;
-            self.amount_in_second = _amount_in_second;
-            self.detal_ms_record = _detal_ms_record;
+            // 停顿刚结束时 不论累计间隔 立即计算
+            let (ending, pausing) = self.anime_quantized(delta_ms, self.debug || resumed, group_info);
+            if pausing {
+                self.iteration_pause_enter(group_info);
+            }
+            self.end_delay_update(ending, group_info);
+        }
+    }
+    /// 量化模式下的推进 - 以推进前的运行时间计算进度
+    /// * `force` 是否不论累计间隔 都进行计算
+    /// * 返回 (是否进入结束延时, 是否进入循环停顿)
+    fn anime_quantized(
+        &mut self,
+        delta_ms: TimeMS,
+        force: bool,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> (bool, bool) {
+        // 延时未结束
+        if self.delay_time_ms - self.running_delay_time_ms > self.frame_ms * 0.75 {
+            self.running_delay_time_ms += delta_ms;
+            if (self.fill_mode.deref() & EFillMode::BACKWARDS.deref()) == *EFillMode::BACKWARDS.deref() {
+                let anime_amount = if self.loop_calc().is_positive_pass(0) { 0. } else { 1. };
+                self.amount_in_second = (anime_amount as TimeMS * self.once_time_ms / 1000.0) as KeyFrameCurveValue + self.from / BASE_FPS as KeyFrameCurveValue;
+                group_info.amount_in_second = self.amount_in_second;
+            }
+            return (false, false);
+        }
+
+        // 正常运行
+        if !self.started {
+            self.started = true;
+            group_info.start_event = true;
+            group_info.delay_end_event = self.delay_time_ms > 0.;
+        }
+
+        self.detal_ms_record += delta_ms;
+
+        // 有效动画帧间隔
+        let mut result = (false, false);
+        if group_info.start_event || self.detal_ms_record >= self.frame_ms * 0.75 || force {
+            let pass_time_ms = (self.once_time_ms - self.frame_ms * 0.5).max(self.frame_ms * 0.5);
+            let loop_calc = self.loop_calc();
+            let loop_passes = loop_calc.loop_passes();
+            let (mut amount, loop_count) = loop_calc.amount(pass_time_ms, self.iteration_range.time(pass_time_ms * loop_passes as TimeMS, self.running_time_ms));
+            let position = self.iteration_range.position(pass_time_ms * loop_passes as TimeMS, self.running_time_ms);
+            group_info.positive_pass = loop_calc.is_positive_pass(self.iteration_range.pass_index(pass_time_ms, loop_passes, self.running_time_ms));
+
+            result = self.update_loop(loop_count, position, &mut amount, group_info);
+
+            if !result.1 {
+                self.looped_count = loop_count;
+            }
+            self.amount_in_second = self.amount_to_second(amount);
+
+            group_info.amount_in_second = self.amount_in_second;
+            group_info.looped_count = loop_count;
+            group_info.running_time_ms = self.running_time_ms;
+            group_info.once_time_ms = pass_time_ms;
+
+            self.step_advance(self.detal_ms_record);
+            self.detal_ms_record = 0.;
+        }
+        result
+    }
+    /// 非量化模式下的推进 - 结果只与累计的间隔时间有关
+    fn anime_step(
+        &mut self,
+        delta_ms: TimeMS,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        let mut delta_ms = delta_ms;
+        // 延时未结束
+        if self.running_delay_time_ms < self.delay_time_ms {
+            self.running_delay_time_ms += delta_ms;
+            if self.running_delay_time_ms < self.delay_time_ms {
+                self.delay_fill(group_info);
+                return;
+            }
+            // 延时结束后的剩余时间 计入运行时间
+            delta_ms = self.running_delay_time_ms - self.delay_time_ms;
+        }
+
+        self.step_start(group_info);
+
+        match self.time_step {
+            ETimeStepMode::Fixed(step) if step > 0. => {
+                self.detal_ms_record += delta_ms;
+                if self.detal_ms_record < step {
+                    self.step_evaluate(group_info);
+                    return;
+                }
+                // 逐个步长推进 每步都处理 循环停顿 循环 和 结束
+                while self.is_playing && self.detal_ms_record >= step {
+                    self.detal_ms_record -= step;
+                    if self.iteration_pause.is_some() {
+                        self.elapsed_ms += step;
+                        if self.iteration_pause_update(group_info) {
+                            self.step_evaluate(group_info);
+                        }
+                    } else if self.end_delay_from_ms.is_some() {
+                        self.elapsed_ms += step;
+                        self.end_delay_update(false, group_info);
+                    } else {
+                        self.step_advance(step);
+                        self.step_evaluate(group_info);
+                    }
+                }
+            },
+            _ => {
+                self.step_advance(delta_ms);
+                self.step_evaluate(group_info);
+            },
+        }
+    }
+    /// 经过时间 推进 step_ms
+    fn step_advance(
+        &mut self,
+        step_ms: TimeMS,
+    ) {
+        self.elapsed_ms += step_ms;
+        self.running_time_ms = match &self.speed_curve {
+            Some(_) => self.running_of(self.elapsed_ms),
+            None => self.running_time_ms + step_ms * self.speed as TimeMS,
+        };
+    }
+    /// 绝对时钟驱动的计算 - 由启动时刻直接得到运行时间, 不累计误差
+    /// * `time_ms` 当前时刻
+    /// * `last_time_ms` 上次计算的时刻 - 启动后首次计算时 作为动画组的启动时刻
+    /// * 量化模式 按连续时间计算, 固定步长模式 运行时间取步长的整数倍
+    /// * 暂停期间的时间不计入运行时间
+    /// * 已播放完成的动画组 回退到结束之前的时刻时 重新激活, 不再触发启动事件
+    pub fn anime_at(
+        &mut self,
+        runtime_infos: &mut RuntimeInfoMap<T>,
+        time_ms: TimeMS,
+        last_time_ms: Option<TimeMS>,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        group_info.last_amount_in_second = group_info.amount_in_second;
+
+        if self.is_playing == false {
+            match self.end_clock_ms() {
+                Some(end) if time_ms < end - 0.001 => {
+                    self.is_playing = true;
+                    self.end_delay_from_ms = None;
+                },
+                Some(_) => {
+                    // 结束后的时间 不作为暂停时间
+                    self.last_clock_ms = Some(time_ms);
+                    return;
+                },
+                None => return,
+            }
+        }
+
+        let rewind = matches!(self.last_clock_ms, Some(last) if time_ms < last);
+        let start = match (self.start_clock_ms, self.last_clock_ms, last_time_ms) {
+            (Some(start), Some(group_last), Some(last)) if group_last < last => start + (last - group_last),
+            (Some(start), _, _) => start,
+            (None, _, Some(last)) => last.min(time_ms),
+            (None, _, None) => time_ms,
+        };
+        self.start_clock_ms = Some(start);
+        self.last_clock_ms = Some(time_ms);
+
+        let elapsed_ms = (time_ms - start).max(0.);
+        if rewind {
+            self.end_delay_from_ms = None;
+        }
+        if self.end_delay_from_ms.is_some() {
+            // 结束延时中 只推进时间
+            self.elapsed_ms = elapsed_ms - self.delay_time_ms + self.start_offset_ms - self.paused_total_ms;
+            self.end_delay_update(false, group_info);
             self.update_to_infos(runtime_infos);
+            return;
+        }
+        if elapsed_ms < self.delay_time_ms {
+            self.running_delay_time_ms = elapsed_ms;
+            self.delay_fill(group_info);
+            self.update_to_infos(runtime_infos);
+            return;
+        }
+        self.running_delay_time_ms = self.delay_time_ms;
+
+        self.step_start(group_info);
+
+        let mut running_ms = elapsed_ms - self.delay_time_ms;
+        if let ETimeStepMode::Fixed(step) = self.time_step {
+            if step > 0. {
+                running_ms = (running_ms / step).floor() * step;
+            }
+        }
+        if rewind {
+            self.iteration_pause_rewind(running_ms + self.start_offset_ms);
+        }
+        self.elapsed_ms = running_ms + self.start_offset_ms - self.paused_total_ms;
+        if !self.iteration_pause_update(group_info) {
+            self.update_to_infos(runtime_infos);
+            return;
+        }
+        self.running_time_ms = self.running_of(self.elapsed_ms);
+
+        self.step_evaluate(group_info);
+        self.update_to_infos(runtime_infos);
+    }
+    /// 绝对时钟驱动时 切换到另一时钟 - 记录的启动时刻 和 上次计算时刻 平移 offset_ms
+    pub fn rebase_clock(&mut self, offset_ms: TimeMS) {
+        self.start_clock_ms = self.start_clock_ms.map(|v| v + offset_ms);
+        self.last_clock_ms = self.last_clock_ms.map(|v| v + offset_ms);
+    }
+    /// 绝对时钟驱动时 播放完成的时刻 - 包含结束延时
+    /// * 未以绝对时钟启动 或 无限循环时 为 None
+    fn end_clock_ms(&self) -> Option<TimeMS> {
+        let start = self.start_clock_ms?;
+        let elapsed_ms = self.elapsed_of(self.active_running_ms()?);
+        Some(start + self.delay_time_ms + elapsed_ms - self.start_offset_ms + self.paused_total_ms + self.end_delay_ms.max(0.))
+    }
+    /// 延时中 按 fill_mode 保持第一帧状态
+    fn delay_fill(
+        &mut self,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        if (self.fill_mode.deref() & EFillMode::BACKWARDS.deref()) == *EFillMode::BACKWARDS.deref() {
+            let amount = if self.loop_calc().is_positive_pass(0) { 0. } else { 1. };
+            self.amount_in_second = self.amount_to_second(amount);
+            group_info.amount_in_second = self.amount_in_second;
+        }
+    }
+    /// 延时结束后的首次计算 触发启动事件
+    fn step_start(
+        &mut self,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        if !self.started {
+            self.started = true;
+            group_info.start_event = true;
+            group_info.delay_end_event = self.delay_time_ms > 0.;
+        }
+    }
+    /// 以当前运行时间 计算进度 和 循环状态
+    fn step_evaluate(
+        &mut self,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        let loop_calc = self.loop_calc();
+        let loop_passes = loop_calc.loop_passes();
+        let (mut amount, loop_count) = loop_calc.amount(self.once_time_ms, self.iteration_range.time(self.once_time_ms * loop_passes as TimeMS, self.running_time_ms));
+        let position = self.iteration_range.position(self.once_time_ms * loop_passes as TimeMS, self.running_time_ms);
+        group_info.positive_pass = loop_calc.is_positive_pass(self.iteration_range.pass_index(self.once_time_ms, loop_passes, self.running_time_ms));
+        let (ending, pausing) = self.update_loop(loop_count, position, &mut amount, group_info);
+        if pausing {
+            // 停顿已结束时 以剩余时间继续计算
+            if self.iteration_pause_enter(group_info) {
+                self.step_evaluate(group_info);
+            }
+            return;
+        }
+
+        self.looped_count = loop_count;
+        self.amount_in_second = self.amount_to_second(amount);
+
+        group_info.amount_in_second = self.amount_in_second;
+        group_info.looped_count = loop_count;
+        group_info.running_time_ms = self.running_time_ms;
+        group_info.once_time_ms = self.once_time_ms;
+
+        self.end_delay_update(ending, group_info);
+    }
+    /// 依据新的循环次数 和 循环位置 更新循环 结束状态
+    /// * 循环位置达到循环范围的结束位置时 结束, 结束位置在循环边界上时 按 fill_mode 修正进度
+    /// * 返回 (是否进入结束延时, 是否进入循环停顿)
+    ///   * 有结束延时时 只进入结束延时, 由 end_delay_update 在结束延时后触发结束事件
+    ///   * 有循环停顿 且未到最后一次循环时 只进入循环停顿, 由 iteration_pause_enter 在停顿后触发循环事件
+    fn update_loop(
+        &mut self,
+        loop_count: u32,
+        position: IterationCount,
+        amount: &mut KeyFrameCurveValue,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> (bool, bool) {
+        let iteration_delay = self.iteration_delay.map(|v| v.is_active()).unwrap_or(false);
+        let end = self.iteration_range.end();
+        if self.looped_count != loop_count {
+            let last = match end {
+                Some(end) => (self.looped_count + 1) as IterationCount >= end,
+                None => false,
+            };
+            if iteration_delay && loop_count > self.looped_count && !last {
+                return (false, true);
+            }
+        }
+        let mut ending = false;
+        match end {
+            Some(end) if position >= end => {
+                // 结束前 未报告的中间循环
+                group_info.loop_crossed += (end.ceil() as u32).saturating_sub(1).saturating_sub(self.looped_count);
+                group_info.loop_event = group_info.loop_crossed > 0;
+                if self.end_delay_ms > 0. {
+                    ending = true;
+                } else {
+                    group_info.end_event = true;
+                    self.is_playing = false;
+                }
+
+                // 结束位置不在循环边界上时 进度已停在结束位置
+                if end.fract() == 0. && (self.fill_mode.deref() & EFillMode::FORWARDS.deref()) == *EFillMode::FORWARDS.deref() {
+                    // 最后一次播放的终点
+                    let loop_calc = self.loop_calc();
+                    let last_pass = ((end * loop_calc.loop_passes() as IterationCount).ceil() as u32).max(1) - 1;
+                    *amount = if loop_calc.is_positive_pass(last_pass) { 1. } else { 0. };
+                }
+            },
+            _ => {
+                if self.looped_count != loop_count {
+                    group_info.loop_crossed += loop_count.saturating_sub(self.looped_count);
+                    group_info.loop_event = true;
+                }
+            },
+        }
+        (ending, false)
+    }
+    /// 播放完成时 进入结束延时, 结束延时已过时 触发结束事件
+    fn end_delay_update(
+        &mut self,
+        ending: bool,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) {
+        if ending && self.end_delay_from_ms.is_none() {
+            let running_ms = self.active_running_ms().unwrap_or(self.running_time_ms);
+            self.end_delay_from_ms = Some(self.elapsed_of(running_ms));
+        }
+        if let Some(from) = self.end_delay_from_ms {
+            if self.elapsed_ms - from >= self.end_delay_ms - 0.001 {
+                self.end_delay_from_ms = None;
+                self.is_playing = false;
+                group_info.end_event = true;
+            }
+        }
+    }
+    /// 是否处于结束延时中
+    pub fn is_end_delaying(&self) -> bool {
+        self.end_delay_from_ms.is_some()
+    }
+    /// 到达循环边界 进入循环停顿 - 运行时间停在边界上, 按设置保持状态
+    /// * 返回 停顿是否已结束
+    fn iteration_pause_enter(
+        &mut self,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> bool {
+        let setting = match self.iteration_delay {
+            Some(setting) => setting,
+            None => return true,
+        };
+        let next_pass = (self.looped_count + 1) * self.loop_calc().loop_passes();
+        let boundary_ms = self.loop_time_ms() * ((self.looped_count + 1) as IterationCount - self.iteration_range.start);
+        let index = self.looped_count.saturating_sub(self.iteration_range.start.floor() as u32);
+        let duration_ms = Self::iteration_pause_ms(&setting, index);
+
+        self.running_time_ms = boundary_ms;
+        self.iteration_pause = Some((self.elapsed_of(boundary_ms), duration_ms));
+
+        let amount = match setting.hold {
+            EIterationHold::LastFrame => if self.loop_calc().is_positive_pass(next_pass - 1) { 1. } else { 0. },
+            EIterationHold::NextFirstFrame => if self.loop_calc().is_positive_pass(next_pass) { 0. } else { 1. },
+        };
+        self.amount_in_second = self.amount_to_second(amount);
+        group_info.amount_in_second = self.amount_in_second;
+        group_info.looped_count = self.looped_count;
+        group_info.running_time_ms = self.running_time_ms;
+
+        self.iteration_pause_update(group_info)
+    }
+    /// 第 index 次循环停顿的时长 - 由种子确定, 与计算的先后无关
+    fn iteration_pause_ms(setting: &IterationDelay, index: u32) -> TimeMS {
+        let mut random = SeededRandom::new(setting.seed);
+        let mut value = random.next_f64();
+        for _ in 0..index {
+            value = random.next_f64();
+        }
+        setting.delay_ms.max(0.) + setting.jitter_ms.max(0.) * value
+    }
+    /// 绝对时钟回退时 由延时结束后的时间 重新得到 已完成的循环停顿 和 循环次数
+    /// * `total_ms` 延时结束后经过的时间 - 包含循环停顿
+    fn iteration_pause_rewind(&mut self, total_ms: TimeMS) {
+        self.iteration_pause = None;
+        self.paused_total_ms = 0.;
+        self.looped_count = self.iteration_range.start.floor() as u32;
+        let setting = match self.iteration_delay {
+            Some(setting) if setting.is_active() => setting,
+            _ => return,
+        };
+        let mut random = SeededRandom::new(setting.seed);
+        loop {
+            let next = (self.looped_count + 1) as IterationCount;
+            // 最后一次循环后 不停顿
+            if let Some(end) = self.iteration_range.end() {
+                if next >= end {
+                    break;
+                }
+            }
+            let boundary_ms = self.elapsed_of(self.loop_time_ms() * (next - self.iteration_range.start)) + self.paused_total_ms;
+            let duration_ms = setting.delay_ms.max(0.) + setting.jitter_ms.max(0.) * random.next_f64();
+            if total_ms < boundary_ms + duration_ms - 0.001 {
+                break;
+            }
+            self.paused_total_ms += duration_ms;
+            self.looped_count += 1;
+        }
+    }
+    /// 循环停顿时间已过时 开始下一次循环, 触发循环事件
+    /// * 返回 是否不在停顿中
+    fn iteration_pause_update(
+        &mut self,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> bool {
+        if let Some((from, duration_ms)) = self.iteration_pause {
+            if self.elapsed_ms - from < duration_ms - 0.001 {
+                return false;
+            }
+            self.iteration_pause = None;
+            self.elapsed_ms -= duration_ms;
+            self.paused_total_ms += duration_ms;
+            self.running_time_ms = self.running_of(self.elapsed_ms);
+            self.looped_count += 1;
+
+            group_info.loop_event = true;
+            group_info.loop_crossed += 1;
+            group_info.looped_count = self.looped_count;
+            group_info.running_time_ms = self.running_time_ms;
+        }
+        true
+    }
+    /// 设置循环之间的停顿
+    pub fn set_iteration_delay(&mut self, setting: Option<IterationDelay>) {
+        self.iteration_delay = setting;
+    }
+    pub fn iteration_delay(&self) -> Option<&IterationDelay> {
+        self.iteration_delay.as_ref()
+    }
+    /// 是否处于循环停顿中
+    pub fn is_iteration_pausing(&self) -> bool {
+        self.iteration_pause.is_some()
+    }
+    /// 经过时间 对应的运行时间
+    fn running_of(&self, elapsed_ms: TimeMS) -> TimeMS {
+        match &self.speed_curve {
+            Some(curve) => curve.integral(elapsed_ms) * self.speed as TimeMS,
+            None => elapsed_ms * self.speed as TimeMS,
+        }
+    }
+    /// 达到运行时间 需要的经过时间
+    fn elapsed_of(&self, running_time_ms: TimeMS) -> TimeMS {
+        let distance_ms = running_time_ms / self.speed.max(0.0001) as TimeMS;
+        match &self.speed_curve {
+            Some(curve) => curve.inverse(distance_ms).unwrap_or(self.elapsed_ms),
+            None => distance_ms,
+        }
+    }
+    /// 设置播放速度曲线 - 运行中设置时 保持当前进度
+    pub fn set_speed_curve(&mut self, curve: Option<SpeedCurve>) {
+        self.speed_curve = curve;
+        if self.is_playing {
+            self.elapsed_ms = self.elapsed_of(self.running_time_ms);
+        }
+    }
+    pub fn speed_curve(&self) -> Option<&SpeedCurve> {
+        self.speed_curve.as_ref()
+    }
+    /// 距离播放结束的剩余时间 - 包含未结束的延时 结束延时 和 循环停顿, 考虑速度曲线
+    /// * 循环停顿 不计随机增加的时长
+    /// * 未在播放, 无限循环 或 速度为 0 无法结束时 返回 None
+    pub fn remaining_time_ms(&self) -> Option<TimeMS> {
+        if !self.is_playing || self.speed <= 0. {
+            return None;
+        }
+        let distance_ms = self.active_running_ms()? / self.speed as TimeMS;
+        let end_ms = match &self.speed_curve {
+            Some(curve) => curve.inverse(distance_ms)?,
+            None => distance_ms,
+        };
+        let delay_ms = (self.delay_time_ms - self.running_delay_time_ms).max(0.);
+        let end_delay_ms = match self.end_delay_from_ms {
+            Some(from) => self.end_delay_ms - (self.elapsed_ms - from),
+            None => self.end_delay_ms,
+        };
+        let (elapsed_ms, mut pause_ms, next_loop) = match self.iteration_pause {
+            Some((from, duration_ms)) => (from, (duration_ms - (self.elapsed_ms - from)).max(0.), self.looped_count + 2),
+            None => (self.elapsed_ms, 0., self.looped_count + 1),
+        };
+        if let (Some(setting), Some(end)) = (self.iteration_delay, self.iteration_range.end()) {
+            pause_ms += (end.ceil() as u32).saturating_sub(next_loop) as TimeMS * setting.delay_ms.max(0.);
+        }
+        Some(delay_ms + (end_ms - elapsed_ms).max(0.) + pause_ms + end_delay_ms.max(0.))
+    }
+    /// 一次循环的时间 - 往复循环包含两次播放
+    fn loop_time_ms(&self) -> TimeMS {
+        self.pass_time_ms() * self.loop_calc().loop_passes() as TimeMS
+    }
+    /// 按循环范围 播放完成时的运行时间 - 无限循环时为 None
+    fn active_running_ms(&self) -> Option<TimeMS> {
+        self.iteration_range.count.map(|count| count * self.loop_time_ms())
+    }
+    /// 进度计算实际使用的 单次播放时间
+    fn pass_time_ms(&self) -> TimeMS {
+        if self.time_step.is_deterministic() || self.start_clock_ms.is_some() {
+            self.once_time_ms
+        } else {
+            (self.once_time_ms - self.frame_ms * 0.5).max(self.frame_ms * 0.5)
         }
     }
     pub fn goto_progress(
@@ -273,7 +899,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
 
         {
             let anime_amount = self.amount_calc.calc(amount);
-            let amount_in_second = anime_amount * self.once_time_ms + self.from / BASE_FPS as KeyFrameCurveValue;
+            let amount_in_second = anime_amount * self.once_time_ms as KeyFrameCurveValue + self.from / BASE_FPS as KeyFrameCurveValue;
 
             let loop_count = 0;
             _looped_count = loop_count;
@@ -309,10 +935,290 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         self.animations.push(target_animation);
         Ok(())
     }
+    /// 挂载在动画组上的帧事件
+    pub fn frame_events(&self) -> &TimeFrameEvents {
+        &self.frame_events
+    }
+    /// 挂载在动画组上的帧事件
+    pub fn frame_events_mut(&mut self) -> &mut TimeFrameEvents {
+        &mut self.frame_events
+    }
+    /// 挂载在指定 Target动画 上的帧事件
+    pub fn target_frame_events_mut(
+        &mut self,
+        target: &T,
+        attr: IDAnimatableAttr,
+    ) -> Option<&mut TimeFrameEvents> {
+        for anime in self.animations.iter_mut() {
+            if anime.target == *target && anime.animation.attr == attr {
+                return Some(anime.frame_events_mut());
+            }
+        }
+        None
+    }
+    /// 是否挂载了帧事件 或 仍处于区间事件中
+    pub fn has_frame_events(&self) -> bool {
+        !self.frame_events.is_empty()
+            || !self.active_ranges.is_empty()
+            || self.animations.iter().any(|anime| !anime.frame_events().is_empty())
+    }
+    /// 当前处于其中的区间事件
+    pub fn active_ranges(&self) -> &Vec<FrameEventData> {
+        &self.active_ranges
+    }
+    /// 动画组进度 在曲线时间轴上的位置 - 秒
+    fn amount_to_second(&self, amount: KeyFrameCurveValue) -> KeyFrameCurveValue {
+        (self.amount_calc.calc(amount) as TimeMS * self.once_time_ms / 1000.0) as KeyFrameCurveValue + self.from / Self::BASE_FPS as KeyFrameCurveValue
+    }
+    /// 查询 各次播放的进度变化 经过的帧事件
+    /// * `passes` 通过 AnimationGroupRuntimeInfo.amount_passes 获得
+    /// * 进度按动画组当前的 from to 和进度曲线 换算到曲线时间轴, 结果按时间先后排列
+    pub fn query_frame_events(
+        &self,
+        passes: &[AmountPass],
+    ) -> Vec<FrameEventData> {
+        self.query_frame_events_timed(passes).drain(..).map(|(_, _, data)| data).collect()
+    }
+    /// 查询 各次播放的进度变化 经过的帧事件 - (第几次播放, 在曲线时间轴上的位置, 帧事件数据)
+    pub fn query_frame_events_timed(
+        &self,
+        passes: &[AmountPass],
+    ) -> Vec<(u32, KeyFrameCurveValue, FrameEventData)> {
+        let mut result: Vec<(u32, KeyFrameCurveValue, FrameEventData)> = vec![];
+        let mut temp = vec![];
+        let (first, last) = match (passes.first(), passes.last()) {
+            (Some(first), Some(last)) => (first.index, last.index),
+            _ => return result,
+        };
+        for pass in passes {
+            if self.frame_event_catch_up == EFrameEventCatchUp::Skip && pass.index != first && pass.index != last {
+                continue;
+            }
+            let from = self.amount_to_second(pass.from);
+            let to = self.amount_to_second(pass.to);
+            self.frame_events.collect(from, to, pass.include_from, &mut temp);
+            for anime in self.animations.iter() {
+                anime.frame_events().collect(from, to, pass.include_from, &mut temp);
+            }
+            if from <= to {
+                temp.sort_by(|a, b| a.0.total_cmp(&b.0));
+            } else {
+                temp.sort_by(|a, b| b.0.total_cmp(&a.0));
+            }
+            temp.drain(..).for_each(|(second, data)| {
+                if self.frame_event_catch_up != EFrameEventCatchUp::Once || !result.iter().any(|item| item.2 == data) {
+                    result.push((pass.index, second, data));
+                }
+            });
+        }
+        result
+    }
+    /// 查询 各次播放的进度变化 产生的区间进入 离开事件, 并更新活动区间
+    /// * 每次播放开始时 先按播放起点更新活动区间, 再依次经过途中的区间边界
+    pub fn query_range_events(
+        &mut self,
+        passes: &[AmountPass],
+    ) -> Vec<(EAnimationEvent, FrameEventData)> {
+        self.query_range_events_timed(passes).drain(..).map(|(_, _, ty, data)| (ty, data)).collect()
+    }
+    /// 查询 区间进入 离开事件 - (第几次播放, 在曲线时间轴上的位置, 事件类型, 区间事件数据)
+    pub fn query_range_events_timed(
+        &mut self,
+        passes: &[AmountPass],
+    ) -> Vec<(u32, KeyFrameCurveValue, EAnimationEvent, FrameEventData)> {
+        let mut result = vec![];
+        let mut temp = vec![];
+        let mut bounds = vec![];
+        for pass in passes {
+            let from = self.amount_to_second(pass.from);
+            let to = self.amount_to_second(pass.to);
+            self.update_active_ranges(from, &mut temp);
+            result.extend(temp.drain(..).map(|(ty, data)| (pass.index, from, ty, data)));
+
+            let (min, max) = (from.min(to), from.max(to));
+            self.frame_events.collect_range_bounds(min, max, &mut bounds);
+            for anime in self.animations.iter() {
+                anime.frame_events().collect_range_bounds(min, max, &mut bounds);
+            }
+            if from <= to {
+                bounds.sort_by(|a, b| a.total_cmp(b));
+            } else {
+                bounds.sort_by(|a, b| b.total_cmp(a));
+            }
+            bounds.push(to);
+            let mut seconds = replace(&mut bounds, vec![]);
+            seconds.drain(..).for_each(|second| {
+                self.update_active_ranges(second, &mut temp);
+                result.extend(temp.drain(..).map(|(ty, data)| (pass.index, second, ty, data)));
+            });
+        }
+        result
+    }
+    /// 离开所有活动区间 - 动画组停止 或 结束时调用
+    pub fn exit_ranges(&mut self) -> Vec<(EAnimationEvent, FrameEventData)> {
+        let mut result = vec![];
+        replace(&mut self.active_ranges, vec![]).drain(..).for_each(|data| {
+            result.push((EAnimationEvent::RangeExit, data));
+        });
+        result
+    }
+    /// 包含指定时刻的区间事件
+    fn ranges_at(&self, second: KeyFrameCurveValue) -> Vec<FrameEventData> {
+        let mut temp = vec![];
+        self.frame_events.collect_ranges_at(second, &mut temp);
+        for anime in self.animations.iter() {
+            anime.frame_events().collect_ranges_at(second, &mut temp);
+        }
+        let mut result = vec![];
+        temp.drain(..).for_each(|data| {
+            if !result.contains(&data) {
+                result.push(data);
+            }
+        });
+        result
+    }
+    /// 将活动区间更新为 包含指定时刻的区间, 先记录离开事件 再记录进入事件
+    fn update_active_ranges(
+        &mut self,
+        second: KeyFrameCurveValue,
+        result: &mut Vec<(EAnimationEvent, FrameEventData)>,
+    ) {
+        let current = self.ranges_at(second);
+        let active = replace(&mut self.active_ranges, vec![]);
+        for data in active.iter() {
+            if !current.contains(data) {
+                result.push((EAnimationEvent::RangeExit, *data));
+            }
+        }
+        for data in current.iter() {
+            if !active.contains(data) {
+                result.push((EAnimationEvent::RangeEnter, *data));
+            }
+        }
+        self.active_ranges = current;
+    }
+    /// 跳转到 当前这次播放 的指定进度
+    /// * `progress` 0~1 - 0 对应 from, 1 对应 to, 与这次播放的方向无关
+    /// * 跳转跨过的帧事件不触发, 区间事件按跳转后的位置 触发进入和离开事件
+    pub fn seek(
+        &mut self,
+        progress: KeyFrameCurveValue,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> Vec<(EAnimationEvent, FrameEventData)> {
+        let progress = progress.max(0.).min(1.);
+        let pass_time_ms = self.pass_time_ms();
+        // 循环起始位置之前的时间
+        let offset_ms = self.iteration_range.start * self.loop_time_ms();
+        let mut index = ((self.running_time_ms + offset_ms) / pass_time_ms).floor() as u32;
+        if let Some(end) = self.iteration_range.end() {
+            let count = (end * self.loop_calc().loop_passes() as IterationCount).ceil() as u32;
+            index = index.min(count.max(1) - 1);
+        }
+        let amount = if self.loop_calc().is_positive_pass(index) { progress } else { 1. - progress };
+
+        self.running_time_ms = ((index as TimeMS + amount as TimeMS) * pass_time_ms - offset_ms).max(0.);
+        self.elapsed_ms = self.elapsed_of(self.running_time_ms);
+        self.detal_ms_record = 0.;
+        self.end_delay_from_ms = None;
+        self.iteration_pause = None;
+        // 绝对时钟驱动时 按跳转后的运行时间 重新计算启动时刻
+        if let (Some(_), Some(last)) = (self.start_clock_ms, self.last_clock_ms) {
+            self.start_clock_ms = Some(last - self.delay_time_ms - self.elapsed_ms + self.start_offset_ms - self.paused_total_ms);
+        }
+        self.amount_in_second = self.amount_to_second(progress);
+
+        group_info.amount_in_second = self.amount_in_second;
+        group_info.last_running_time_ms = self.running_time_ms;
+        group_info.running_time_ms = self.running_time_ms;
+
+        let mut result = vec![];
+        self.update_active_ranges(self.amount_in_second, &mut result);
+        result
+    }
+    /// 移除 作用于目标的 Target动画
+    /// * 返回被移除的动画
+    /// * 总帧数变化时 按比例调整 group_info 中的运行时间, 参见 reset_max_frame
+    pub fn remove_target(
+        &mut self,
+        target: &T,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> Vec<AnimationInfo> {
+        let mut result = vec![];
+        let mut animations = replace(&mut self.animations, vec![]);
+        animations.drain(..).for_each(|item| {
+            if item.target == *target {
+                result.push(item.animation);
+            } else {
+                self.animations.push(item);
+            }
+        });
+        if result.len() > 0 {
+            self.reset_max_frame(group_info);
+        }
+        result
+    }
+    /// 更新 使用指定曲线的动画 的曲线描述信息
+    /// * 返回 是否有动画使用该曲线
+    /// * 总帧数变化时 按比例调整 group_info 中的运行时间, 参见 reset_max_frame
+    pub fn update_curve_info(
+        &mut self,
+        ty: KeyFrameDataType,
+        curve_id: FrameCurveInfoID,
+        curve_info: FrameCurveInfo,
+        group_info: &mut AnimationGroupRuntimeInfo,
+    ) -> bool {
+        let mut changed = false;
+        for anime in self.animations.iter_mut() {
+            if anime.animation.ty == ty && anime.animation.curve_id == curve_id {
+                anime.animation.curve_info = curve_info;
+                changed = true;
+            }
+        }
+        if changed {
+            self.reset_max_frame(group_info);
+        }
+        changed
+    }
+    /// 依据动画集合 重新计算最大帧数
+    /// * 运行中的动画组 按比例调整 from to 和已运行时间, 保持归一化的进度
+    /// * group_info 中的运行时间和进度 同步调整, 避免下次计算时 重复经过已触发的帧事件
+    fn reset_max_frame(&mut self, group_info: &mut AnimationGroupRuntimeInfo) {
+        let old_total = self.max_frame();
+
+        self.max_frame = 0.;
+        for anime in self.animations.iter() {
+            self.max_frame = KeyFrameCurveValue::max(self.max_frame, anime.animation.get_max_frame_for_running_speed(Self::BASE_FPS));
+        }
+
+        if self.is_playing {
+            let total = self.max_frame();
+            if old_total > 0. && total != old_total {
+                let ratio = total / old_total;
+                let old_once_time_ms = self.once_time_ms;
+                self.from *= ratio;
+                self.to *= ratio;
+                self.amount_in_second *= ratio;
+                group_info.amount_in_second *= ratio;
+                group_info.last_amount_in_second *= ratio;
+                self.once_time();
+                if old_once_time_ms > 0. {
+                    let time_ratio = self.once_time_ms / old_once_time_ms;
+                    self.running_time_ms *= time_ratio;
+                    self.elapsed_ms = self.elapsed_of(self.running_time_ms);
+                    group_info.last_running_time_ms *= time_ratio;
+                    group_info.running_time_ms = self.running_time_ms;
+                    group_info.once_time_ms *= time_ratio;
+                }
+            }
+        } else {
+            self.to = self.max_frame;
+        }
+    }
     /// 启动动画组 - 完整播放,不关心动画到底设计了多少帧
     /// * `seconds` 播放时长 - 秒
     /// * `loop_mode` 循环模式
     /// * `amount_calc_between_frame` 关键帧之间 进度曲线
+    /// * `delay_time_ms` 延时 - 负值时 从播放途中开始, 跳过对应的时间
     pub fn start_complete(
         &mut self,
         seconds: KeyFrameCurveValue,
@@ -320,7 +1226,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         frame_per_second: FramePerSecond,
         amount_calc_between_frame: AnimationAmountCalc,
         group_info: &mut AnimationGroupRuntimeInfo,
-        delay_time_ms: KeyFrameCurveValue,
+        delay_time_ms: TimeMS,
         fillmode: EFillMode,
     ) {
         let speed = 1.0 / seconds;
@@ -335,6 +1241,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
     /// * `to` 指定动画组的结束帧百分比位置 - 0~1
     /// * `frame_per_second` 指定动画组每秒运行多少帧 - 影响动画流畅度和计算性能
     /// * `amount_calc_between_frame` 关键帧之间 进度曲线
+    /// * `delay_time_ms` 延时 - 负值时 从播放途中开始, 跳过对应的时间
     pub fn start_with_progress(
         &mut self,
         speed: KeyFrameCurveValue,
@@ -344,7 +1251,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         frame_per_second: FramePerSecond,
         group_info: &mut AnimationGroupRuntimeInfo,
         amount_calc_between_frame: AnimationAmountCalc,
-        delay_time_ms: KeyFrameCurveValue,
+        delay_time_ms: TimeMS,
         fillmode: EFillMode,
     ) {
         self.start(speed.abs(), loop_mode, from * self.max_frame(), to * self.max_frame(), frame_per_second, group_info, amount_calc_between_frame, delay_time_ms, fillmode)
@@ -356,6 +1263,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
     /// * `to` 指定动画组的结束帧位置
     /// * `frame_per_second` 指定动画组每秒运行多少帧 - 影响动画流畅度和计算性能
     /// * `amount_calc_between_frame` 关键帧之间 进度曲线
+    /// * `delay_time_ms` 延时 - 负值时 从播放途中开始, 跳过对应的时间
     fn start(
         &mut self,
         speed: KeyFrameCurveValue,
@@ -365,7 +1273,7 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         frame_per_second: FramePerSecond,
         group_info: &mut AnimationGroupRuntimeInfo,
         amount_calc_between_frame: AnimationAmountCalc,
-        delay_time_ms: KeyFrameCurveValue,
+        delay_time_ms: TimeMS,
         fillmode: EFillMode,
     ) {
         if self.is_playing == true {
@@ -375,11 +1283,19 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         self.is_playing = true;
         self.speed = speed.abs();
         self.running_time_ms = 0.;
+        self.elapsed_ms = 0.;
         self.looped_count = 0;
         self.detal_ms_record = 0.;
         self.amount_in_second = 0.;
-        self.delay_time_ms = delay_time_ms;
+        self.delay_time_ms = delay_time_ms.max(0.);
+        self.start_offset_ms = (-delay_time_ms).max(0.);
         self.running_delay_time_ms = 0.;
+        self.end_delay_from_ms = None;
+        self.iteration_pause = None;
+        self.paused_total_ms = 0.;
+        self.started = false;
+        self.start_clock_ms = None;
+        self.last_clock_ms = None;
         self.fill_mode = fillmode;
 
         let (from, to) = (KeyFrameCurveValue::min(from, to), KeyFrameCurveValue::max(from, to));
@@ -392,37 +1308,32 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
 
         self.once_time();
 
+        self.elapsed_ms = self.start_offset_ms;
+        self.running_time_ms = self.running_of(self.elapsed_ms);
+
         self.amount_calc_between_frame = Arc::new(amount_calc_between_frame);
 
-        match loop_mode {
-            ELoopMode::Opposite(v) => {
-                self.loop_count = v;
-                self.amount_in_second = to / Self::BASE_FPS as KeyFrameCurveValue;
-            },
-            ELoopMode::OppositePly(v) => {
-                self.loop_count = v;
-                self.amount_in_second = to / Self::BASE_FPS as KeyFrameCurveValue;
-            },
-            ELoopMode::Positive(v) => {
-                self.loop_count = v;
-                self.amount_in_second = from / Self::BASE_FPS as KeyFrameCurveValue;
-            },
-            ELoopMode::PositivePly(v) => {
-                self.loop_count = v;
-                self.amount_in_second = from / Self::BASE_FPS as KeyFrameCurveValue;
-            },
-            ELoopMode::Not => {
-                self.loop_count = Some(1);
-                self.amount_in_second = from / Self::BASE_FPS as KeyFrameCurveValue;
-            },
-        }
+        let first = if self.loop_calc().is_positive_pass(0) { from } else { to };
+        self.amount_in_second = first / Self::BASE_FPS as KeyFrameCurveValue;
+
+        self.iteration_range = self.iteration.resolve(loop_mode, self.loop_calc());
+        self.looped_count = self.iteration_range.start.floor() as u32;
 
         group_info.amount_in_second = self.amount_in_second;
         group_info.last_amount_in_second = self.amount_in_second;
         group_info.start_event = false;
         group_info.loop_event = false;
         group_info.end_event = false;
-        group_info.looped_count = 0;
+        group_info.delay_end_event = false;
+        group_info.last_looped_count = self.looped_count;
+        group_info.looped_count = self.looped_count;
+        group_info.loop_crossed = 0;
+        group_info.last_running_time_ms = self.running_time_ms;
+        group_info.running_time_ms = self.running_time_ms;
+        group_info.once_time_ms = self.once_time_ms;
+        group_info.loop_mode = loop_mode;
+        group_info.positive_pass = self.loop_calc().is_positive_pass(0);
+        group_info.iteration = self.iteration_range;
     }
     /// 启停止动画组
     pub fn stop(
@@ -430,19 +1341,42 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
     ) {
         self.is_playing = false;
     }
+    /// 是否已启动且未结束 - 暂停中的动画组也返回 true
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
     fn loop_mode(
         &mut self,
         mode: ELoopMode,
     ) {
         self.loop_mode = mode;
-        self.amount = get_amount_calc(mode);
+    }
+    /// 实际使用的循环模式
+    fn loop_calc(&self) -> &dyn TLoopMode {
+        match self.loop_calc.as_deref() {
+            Some(loop_calc) => loop_calc,
+            None => &self.loop_mode,
+        }
+    }
+    /// 设置自定义循环模式 - None 时使用启动时指定的 ELoopMode
+    /// * 播放次数 仍由 ELoopMode 或 循环范围 指定
+    pub fn set_loop_calc(&mut self, loop_calc: Option<Arc<dyn TLoopMode>>) {
+        self.loop_calc = loop_calc;
+    }
+    /// 实际使用的循环模式下 每次循环包含的播放次数
+    pub fn loop_passes(&self) -> u32 {
+        self.loop_calc().loop_passes().max(1)
+    }
+    /// 本次计算中 动画组经过的各次播放的进度变化 - 按实际使用的循环模式计算
+    pub fn amount_passes(&self, group_info: &AnimationGroupRuntimeInfo) -> Vec<AmountPass> {
+        get_amount_passes(self.loop_calc(), &group_info.iteration, group_info.once_time_ms, group_info.last_running_time_ms, group_info.running_time_ms, group_info.start_event)
     }
     /// 动画组执行一轮需要的时间 从 from 到 to
     fn once_time(
         &mut self,
     ) {
         // println!("self.from {}, self.to {}", self.from, self.to);
-        self.once_time_ms = (self.to - self.from) as KeyFrameCurveValue / Self::BASE_FPS as KeyFrameCurveValue * 1000.0;
+        self.once_time_ms = (self.to - self.from) as TimeMS / Self::BASE_FPS as TimeMS * 1000.0;
     }
     fn from(
         &mut self,
@@ -464,15 +1398,20 @@ impl<T: Clone + PartialEq + Eq + Hash> AnimationGroup<T> {
         if frame_per_second == 0 {
             Err(EAnimationError::AnimationFramePerSecondCannotZero)
         } else {
-            self.frame_ms = 1000. / frame_per_second as KeyFrameCurveValue;
+            self.frame_ms = 1000. / frame_per_second as TimeMS;
 
             Ok(())
         }
     }
-    fn update_to_infos(
+    /// 将当前进度 更新到曲线计算信息
+    /// * 结束延时中 未设置 EFillMode::FORWARDS 时不更新
+    pub fn update_to_infos(
         &self,
         runtime_infos: &mut RuntimeInfoMap<T>,
     ) {
+        if self.end_delay_from_ms.is_some() && (self.fill_mode.deref() & EFillMode::FORWARDS.deref()) != *EFillMode::FORWARDS.deref() {
+            return;
+        }
         for anime in self.animations.iter() {
             let temp = RuntimeInfo {
                 // group_info: AnimationGroupRuntimeInfo {
@@ -552,104 +1491,3 @@ impl<T: Clone + PartialEq + Eq + Hash> TAnimatableTargetModifier<f32> for Animat
     }
 }
 
-
-fn _anime(
-    _is_playing: &mut bool,
-    delay_time_ms: TimeMS,
-    _running_delay_time_ms: &mut TimeMS,
-    frame_ms: TimeMS,
-    loop_mode: ELoopMode,
-    once_time_ms: TimeMS,
-    from: KeyFrameCurveValue,
-    _running_time_ms: &mut TimeMS,
-    _looped_count: &mut u32,
-    amount_calc: &AnimationAmountCalc,
-    speed: KeyFrameCurveValue,
-    fill_mode: EFillMode,
-    debug: bool,
-    amount: &fn(f32, f32) -> (f32, u32),
-
-    _loop_count: &Option<u32>,
-    _amount_in_second: &mut KeyFrameCurveValue,
-    _detal_ms_record: &mut TimeMS,
-
-    delta_ms: KeyFrameCurveValue,
-    group_info: &mut AnimationGroupRuntimeInfo,
-) {
-    // 延时未结束
-    if delay_time_ms - *_running_delay_time_ms > frame_ms * 0.75 {
-        *_running_delay_time_ms += delta_ms;
-        if (fill_mode.deref() & EFillMode::BACKWARDS.deref()) == *EFillMode::BACKWARDS.deref() {
-            let anime_amount = match loop_mode {
-                ELoopMode::Not => 0.,
-                ELoopMode::Positive(_) => 0.,
-                ELoopMode::Opposite(_) => 1.,
-                ELoopMode::PositivePly(_) => 0.,
-                ELoopMode::OppositePly(_) => 1.,
-            };
-            let amount_in_second = anime_amount * once_time_ms / (1000.0 as KeyFrameCurveValue) + from / BASE_FPS as KeyFrameCurveValue;
-            *_amount_in_second = amount_in_second;
-            group_info.amount_in_second = amount_in_second;
-
-            // self.update_to_infos(runtime_infos);
-        }
-        return;
-    }
-
-    // 正常运行
-    if _running_time_ms.abs() < 0.001 {
-        group_info.start_event = true;
-    }
-
-    *_detal_ms_record += delta_ms;
-    // log::trace!(">>>>>>>>>>>>>>>> detal_ms_record {}, frame_ms {}", self.detal_ms_record, self.frame_ms);
-
-    // 有效动画帧间隔
-    if group_info.start_event || *_detal_ms_record >= frame_ms * 0.75 || debug {
-        let amount_call = amount;
-
-        let (mut amount, loop_count) = amount_call((once_time_ms - frame_ms * 0.5).max(frame_ms * 0.5), *_running_time_ms);
-
-        if *_looped_count != loop_count {
-            match _loop_count {
-                Some(count) => {
-                    if *count <= loop_count {
-                        group_info.end_event = true;
-                        *_is_playing = false;
-
-                        if (fill_mode.deref() & EFillMode::FORWARDS.deref()) == *EFillMode::FORWARDS.deref() {
-                            amount = match loop_mode {
-                                ELoopMode::Not => 1.,
-                                ELoopMode::Positive(_) => 1.,
-                                ELoopMode::Opposite(_) => 0.,
-                                ELoopMode::PositivePly(_) => 0.,
-                                ELoopMode::OppositePly(_) => 1.,
-                            }
-                        }
-                    } else {
-                        group_info.loop_event = true;
-                    }
-                },
-                None => {
-                    group_info.loop_event = true;
-                },
-            }
-        }
-
-        let anime_amount = amount_calc.calc(amount);
-        let amount_in_second = anime_amount * once_time_ms / (1000.0 as KeyFrameCurveValue) + from / BASE_FPS as KeyFrameCurveValue;
-
-        // log::trace!("once_time {}, delay_time {}, amount {}, anime_amount {}, amount_in_second {}", self.once_time_ms, self.running_time_ms, amount, anime_amount, amount_in_second);
-
-        *_looped_count = loop_count;
-        *_amount_in_second = amount_in_second;
-
-        group_info.amount_in_second = amount_in_second;
-        group_info.looped_count = loop_count;
-
-        *_running_time_ms += *_detal_ms_record * speed;
-        *_detal_ms_record = 0.;
-
-        // self.update_to_infos(runtime_infos);
-    }
-}
\ No newline at end of file
@@ -1,6 +1,9 @@
 use std::mem::replace;
 
-use crate::{animation_group::AnimationGroupID, error::EAnimationError};
+use pi_curves::curve::frame::KeyFrameCurveValue;
+use pi_null::Null;
+
+use crate::{animation_group::AnimationGroupID, error::EAnimationError, animation_command::AnimationCommands, base::TimeMS, curve_frame_event::FrameEventData};
 
 #[derive(Debug, Clone, Copy)]
 pub enum EAnimationEventResult {
@@ -8,116 +11,396 @@ pub enum EAnimationEventResult {
     RemoveListen,
 }
 
+/// 监听回调 返回错误时的处理方式
+/// * 直接调用 AnimationListener 的 on_*_with 接口时 错误总是返回给调用方, 不输出日志
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EListenerErrorPolicy {
+    /// 保留回调 - 通过 AnimationEventDispatcher 派发时 输出警告日志
+    Keep,
+    /// 移除回调 - 通过 AnimationEventDispatcher 派发时 输出警告日志
+    #[default]
+    Remove,
+    /// 保留回调, 错误由 派发接口 返回给调用方
+    Propagate,
+}
+
 pub type OnStart = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
 pub type OnEnd = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
 pub type OnLoop = Box<dyn Fn(u32) -> Result<EAnimationEventResult, EAnimationError>>;
 pub type OnFrameEvent<D> = Box<dyn Fn(Vec<D>) -> Result<EAnimationEventResult, EAnimationError>>;
+pub type OnPause = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
+pub type OnStop = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
+/// 区间事件 - 区间事件数据
+pub type OnRangeEvent<D> = Box<dyn Fn(D) -> Result<EAnimationEventResult, EAnimationError>>;
+pub type OnCancel = Box<dyn Fn(EAnimationEndReason) -> Result<EAnimationEventResult, EAnimationError>>;
+pub type OnRemove = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
+pub type OnDelayEnd = Box<dyn Fn() -> Result<EAnimationEventResult, EAnimationError>>;
+/// 一次循环开始 - 第几次循环
+pub type OnIterationStart = Box<dyn Fn(u32) -> Result<EAnimationEventResult, EAnimationError>>;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EAnimationEvent {
     None,
     Start,
     End,
     Loop,
     FrameEvent,
+    /// 进入区间事件
+    RangeEnter,
+    /// 离开区间事件
+    RangeExit,
+    /// 暂停事件
+    Pause,
+    /// 停止事件
+    Stop,
+    /// 未播放完成即被中断
+    Cancel,
+    /// 动画组被删除
+    Remove,
+    /// 延时结束
+    DelayEnd,
+    /// 一次循环开始
+    IterationStart,
+}
+
+/// 动画组结束的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EAnimationEndReason {
+    /// 播放完成
+    Completed,
+    /// 被停止
+    Stopped,
+    /// 动画组被删除
+    Removed,
+}
+
+/// 事件发生时 动画组的播放位置
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AnimationPosition {
+    /// 循环次数
+    pub looped_count: u32,
+    /// 在曲线时间轴上的位置 - 秒
+    pub amount_in_second: KeyFrameCurveValue,
+    /// 动画组的有效运行时间
+    pub running_time_ms: TimeMS,
+}
+
+/// 动画组事件 - 由 AnimationContextAmount 在每次计算后产生, 可拉取 或 转发到其他线程
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AnimationEvent {
+    Start(AnimationGroupID),
+    End(AnimationGroupID),
+    /// 循环事件 - 循环次数
+    Loop(AnimationGroupID, u32),
+    Pause(AnimationGroupID, AnimationPosition),
+    Stop(AnimationGroupID, AnimationPosition),
+    /// 帧事件 - 帧事件数据
+    FrameEvent(AnimationGroupID, FrameEventData),
+    /// 进入区间事件 - 区间事件数据
+    RangeEnter(AnimationGroupID, FrameEventData),
+    /// 离开区间事件 - 区间事件数据
+    RangeExit(AnimationGroupID, FrameEventData),
+    /// 未播放完成即被中断 - 中断原因 为 Stopped 或 Removed
+    Cancel(AnimationGroupID, EAnimationEndReason, AnimationPosition),
+    /// 动画组被删除 - 动画组的监听器在删除时已移除, 只有全局监听 和 事件拉取 能收到
+    Remove(AnimationGroupID, AnimationPosition),
+    /// 延时结束, 紧接着 Start 事件
+    DelayEnd(AnimationGroupID),
+    /// 一次循环开始 - 第几次循环, 从 0 开始
+    IterationStart(AnimationGroupID, u32),
+}
+
+impl AnimationEvent {
+    /// 以事件类型和参数创建 - 参数为 循环次数 或 帧事件数据
+    /// * 携带播放位置 或 原因的事件 返回 None
+    pub fn new(group: AnimationGroupID, kind: EAnimationEvent, param: u32) -> Option<Self> {
+        match kind {
+            EAnimationEvent::Start => Some(AnimationEvent::Start(group)),
+            EAnimationEvent::End => Some(AnimationEvent::End(group)),
+            EAnimationEvent::Loop => Some(AnimationEvent::Loop(group, param)),
+            EAnimationEvent::FrameEvent => Some(AnimationEvent::FrameEvent(group, param as FrameEventData)),
+            EAnimationEvent::RangeEnter => Some(AnimationEvent::RangeEnter(group, param as FrameEventData)),
+            EAnimationEvent::RangeExit => Some(AnimationEvent::RangeExit(group, param as FrameEventData)),
+            EAnimationEvent::DelayEnd => Some(AnimationEvent::DelayEnd(group)),
+            EAnimationEvent::IterationStart => Some(AnimationEvent::IterationStart(group, param)),
+            _ => None,
+        }
+    }
+    pub fn group(&self) -> AnimationGroupID {
+        match self {
+            AnimationEvent::Start(group) => *group,
+            AnimationEvent::End(group) => *group,
+            AnimationEvent::Loop(group, _) => *group,
+            AnimationEvent::Pause(group, _) => *group,
+            AnimationEvent::Stop(group, _) => *group,
+            AnimationEvent::FrameEvent(group, _) => *group,
+            AnimationEvent::RangeEnter(group, _) => *group,
+            AnimationEvent::RangeExit(group, _) => *group,
+            AnimationEvent::Cancel(group, _, _) => *group,
+            AnimationEvent::Remove(group, _) => *group,
+            AnimationEvent::DelayEnd(group) => *group,
+            AnimationEvent::IterationStart(group, _) => *group,
+        }
+    }
+    /// 事件类型
+    pub fn kind(&self) -> EAnimationEvent {
+        match self {
+            AnimationEvent::Start(_) => EAnimationEvent::Start,
+            AnimationEvent::End(_) => EAnimationEvent::End,
+            AnimationEvent::Loop(_, _) => EAnimationEvent::Loop,
+            AnimationEvent::Pause(_, _) => EAnimationEvent::Pause,
+            AnimationEvent::Stop(_, _) => EAnimationEvent::Stop,
+            AnimationEvent::FrameEvent(_, _) => EAnimationEvent::FrameEvent,
+            AnimationEvent::RangeEnter(_, _) => EAnimationEvent::RangeEnter,
+            AnimationEvent::RangeExit(_, _) => EAnimationEvent::RangeExit,
+            AnimationEvent::Cancel(_, _, _) => EAnimationEvent::Cancel,
+            AnimationEvent::Remove(_, _) => EAnimationEvent::Remove,
+            AnimationEvent::DelayEnd(_) => EAnimationEvent::DelayEnd,
+            AnimationEvent::IterationStart(_, _) => EAnimationEvent::IterationStart,
+        }
+    }
+    /// 事件参数 - 循环次数 或 帧事件数据, 其他事件为 0
+    pub fn param(&self) -> u32 {
+        match self {
+            AnimationEvent::Loop(_, count) => *count,
+            AnimationEvent::FrameEvent(_, data) => *data as u32,
+            AnimationEvent::RangeEnter(_, data) => *data as u32,
+            AnimationEvent::RangeExit(_, data) => *data as u32,
+            AnimationEvent::IterationStart(_, index) => *index,
+            _ => 0,
+        }
+    }
+    /// 事件携带的播放位置
+    pub fn position(&self) -> Option<AnimationPosition> {
+        match self {
+            AnimationEvent::Pause(_, position) => Some(*position),
+            AnimationEvent::Stop(_, position) => Some(*position),
+            AnimationEvent::Cancel(_, _, position) => Some(*position),
+            AnimationEvent::Remove(_, position) => Some(*position),
+            _ => None,
+        }
+    }
+}
+
+/// 事件回调参数
+#[derive(Debug, Clone, Copy)]
+pub struct AnimationEventPayload {
+    pub group: AnimationGroupID,
+    pub event: EAnimationEvent,
+    /// 循环次数
+    pub looped_count: u32,
+    /// 动画组在曲线时间轴上的位置 - 秒
+    pub amount_in_second: KeyFrameCurveValue,
+    /// 动画组的有效运行时间
+    pub running_time_ms: TimeMS,
+    /// 帧事件 区间事件 携带的数据, 其他事件为 0
+    pub data: FrameEventData,
 }
 
+/// 可变状态的事件回调 - 每个事件调用一次
+/// * 通过 AnimationCommands 提交的操作 在本次派发结束后执行
+pub type OnAnimationEvent = Box<dyn FnMut(&AnimationEventPayload, &mut AnimationCommands) -> Result<EAnimationEventResult, EAnimationError>>;
+
 pub struct AnimationListener<D: Clone> {
     pub group: AnimationGroupID,
     pub on_start: Vec<OnStart>,
     pub on_end: Vec<OnEnd>,
     pub on_loop: Vec<OnLoop>,
     pub on_frame_event: Vec<OnFrameEvent<D>>,
+    pub on_pause: Vec<OnPause>,
+    pub on_stop: Vec<OnStop>,
+    pub on_range_enter: Vec<OnRangeEvent<D>>,
+    pub on_range_exit: Vec<OnRangeEvent<D>>,
+    pub on_cancel: Vec<OnCancel>,
+    pub on_remove: Vec<OnRemove>,
+    pub on_delay_end: Vec<OnDelayEnd>,
+    pub on_iteration_start: Vec<OnIterationStart>,
 }
 
-impl<D: Clone> AnimationListener<D> {
-    pub fn new(group: AnimationGroupID) -> Self {
+/// 默认为 监听所有动画组 且没有回调的监听器
+impl<D: Clone> Default for AnimationListener<D> {
+    fn default() -> Self {
         Self {
-            group,
+            group: AnimationGroupID::null(),
             on_start: vec![],
             on_end: vec![],
             on_loop: vec![],
             on_frame_event: vec![],
+            on_pause: vec![],
+            on_stop: vec![],
+            on_range_enter: vec![],
+            on_range_exit: vec![],
+            on_cancel: vec![],
+            on_remove: vec![],
+            on_delay_end: vec![],
+            on_iteration_start: vec![],
         }
     }
+}
+
+impl<D: Clone> AnimationListener<D> {
+    pub fn new(group: AnimationGroupID) -> Self {
+        Self {
+            group,
+            ..Default::default()
+        }
+    }
+    /// 创建 监听所有动画组的监听器 - 注册到 AnimationEventDispatcher 使用
+    pub fn global() -> Self {
+        Self::default()
+    }
+    /// 是否为 监听所有动画组的监听器
+    pub fn is_global(&self) -> bool {
+        self.group.is_null()
+    }
+    /// 调用 on_start 回调 - 出错的回调被移除, 错误返回给调用方
     pub fn on_start(
         &mut self,
-    ) {
-        let mut temp = replace(&mut self.on_start, vec![]);
-        temp.drain(..).for_each(|call| {
-            match call() {
-                Ok(result) => match result {
-                    EAnimationEventResult::None => {
-                        self.on_start.push(call)
-                    },
-                    EAnimationEventResult::RemoveListen => {
-                    },
-                },
-                Err(_) => {},
-            }
-        });
-        // match &self.on_start {
-        //     Some(call) => match call() {
-        //         Ok(result) => match result {
-        //             EAnimationEventResult::None => {},
-        //             EAnimationEventResult::RemoveListen => { self.on_start = None; },
-        //         },
-        //         Err(_) => {},
-        //     },
-        //     None => {},
-        // }
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_start_with(EListenerErrorPolicy::Remove))
     }
     pub fn on_end(
         &mut self,
-    ) {
-        let mut temp = replace(&mut self.on_end, vec![]);
-        temp.drain(..).for_each(|call| {
-            match call() {
-                Ok(result) => match result {
-                    EAnimationEventResult::None => {
-                        self.on_end.push(call)
-                    },
-                    EAnimationEventResult::RemoveListen => {
-                    },
-                },
-                Err(_) => {},
-            }
-        });
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_end_with(EListenerErrorPolicy::Remove))
     }
     pub fn on_loop(
         &mut self,
         loop_count: u32,
-    ) {
-        let mut temp = replace(&mut self.on_loop, vec![]);
-        temp.drain(..).for_each(|call| {
-            match call(loop_count) {
-                Ok(result) => match result {
-                    EAnimationEventResult::None => {
-                        self.on_loop.push(call)
-                    },
-                    EAnimationEventResult::RemoveListen => {
-                    },
-                },
-                Err(_) => {},
-            }
-        });
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_loop_with(loop_count, EListenerErrorPolicy::Remove))
     }
     pub fn on_frame(
         &mut self,
         frame_datas: Vec<D>,
-    ) {
-        let mut temp = replace(&mut self.on_frame_event, vec![]);
-        temp.drain(..).for_each(|call| {
-            match call(frame_datas.clone()) {
-                Ok(result) => match result {
-                    EAnimationEventResult::None => {
-                        self.on_frame_event.push(call)
-                    },
-                    EAnimationEventResult::RemoveListen => {
-                    },
-                },
-                Err(_) => {},
-            }
-        });
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_frame_with(frame_datas, EListenerErrorPolicy::Remove))
+    }
+    pub fn on_pause(
+        &mut self,
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_pause_with(EListenerErrorPolicy::Remove))
+    }
+    pub fn on_stop(
+        &mut self,
+    ) -> Result<(), Vec<EAnimationError>> {
+        listens_result(self.on_stop_with(EListenerErrorPolicy::Remove))
     }
-}
\ No newline at end of file
+    /// 调用 on_start 回调 - 返回回调产生的错误, 出错回调是否保留由 `policy` 决定
+    pub fn on_start_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_start, policy, |call| call())
+    }
+    pub fn on_end_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_end, policy, |call| call())
+    }
+    pub fn on_loop_with(
+        &mut self,
+        loop_count: u32,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_loop, policy, |call| call(loop_count))
+    }
+    pub fn on_frame_with(
+        &mut self,
+        frame_datas: Vec<D>,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_frame_event, policy, |call| call(frame_datas.clone()))
+    }
+    pub fn on_pause_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_pause, policy, |call| call())
+    }
+    pub fn on_stop_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_stop, policy, |call| call())
+    }
+    pub fn on_range_enter_with(
+        &mut self,
+        data: D,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_range_enter, policy, |call| call(data.clone()))
+    }
+    pub fn on_range_exit_with(
+        &mut self,
+        data: D,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_range_exit, policy, |call| call(data.clone()))
+    }
+    pub fn on_cancel_with(
+        &mut self,
+        reason: EAnimationEndReason,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_cancel, policy, |call| call(reason))
+    }
+    pub fn on_remove_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_remove, policy, |call| call())
+    }
+    pub fn on_delay_end_with(
+        &mut self,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_delay_end, policy, |call| call())
+    }
+    pub fn on_iteration_start_with(
+        &mut self,
+        index: u32,
+        policy: EListenerErrorPolicy,
+    ) -> Vec<EAnimationError> {
+        call_listens(&mut self.on_iteration_start, policy, |call| call(index))
+    }
+}
+
+fn listens_result(errors: Vec<EAnimationError>) -> Result<(), Vec<EAnimationError>> {
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// 依次调用回调列表
+/// * 返回 RemoveListen 的回调被移除
+/// * 返回错误的回调 按 `policy` 保留或移除, 错误全部返回
+fn call_listens<C>(
+    list: &mut Vec<C>,
+    policy: EListenerErrorPolicy,
+    mut call: impl FnMut(&C) -> Result<EAnimationEventResult, EAnimationError>,
+) -> Vec<EAnimationError> {
+    let mut errors = vec![];
+    let mut temp = replace(list, vec![]);
+    temp.drain(..).for_each(|item| {
+        match call(&item) {
+            Ok(result) => match result {
+                EAnimationEventResult::None => {
+                    list.push(item)
+                },
+                EAnimationEventResult::RemoveListen => {
+                },
+            },
+            Err(e) => {
+                if policy != EListenerErrorPolicy::Remove {
+                    list.push(item);
+                }
+                errors.push(e);
+            },
+        }
+    });
+    errors
+}
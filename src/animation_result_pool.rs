@@ -23,6 +23,15 @@ pub trait TypeAnimationResultPool<F: FrameDataValue, T> {
         id_attr: IDAnimatableAttr,
         result: AnimeResult<F>,
     ) -> Result<(), EAnimationError>;
+    /// 清理动画结果
+    fn reset(
+        &mut self,
+    ) {}
+    /// 移除 目标 的记录
+    fn remove_target(
+        &mut self,
+        _id_target: T,
+    ) {}
 }
 
 /// 实现一个二维数组保存的动画数据类型的动画结果池
@@ -53,6 +62,11 @@ impl<F: FrameDataValue> Default for TypeAnimationResultPoolDefault<F> {
 }
 
 impl<F: FrameDataValue> TypeAnimationResultPool<F, DefaultKey> for TypeAnimationResultPoolDefault<F> {
+    fn reset(
+        &mut self,
+    ) {
+        TypeAnimationResultPoolDefault::reset(self)
+    }
     fn record_target(
         &mut self,
         id_target: DefaultKey,
@@ -68,6 +82,12 @@ impl<F: FrameDataValue> TypeAnimationResultPool<F, DefaultKey> for TypeAnimation
         //     }
         // }
     }
+    fn remove_target(
+        &mut self,
+        id_target: DefaultKey,
+    ) {
+        self.result.remove(id_target);
+    }
     fn record_result(
         &mut self,
         id_target: DefaultKey,
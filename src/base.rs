@@ -1,9 +1,8 @@
 use std::ops::Deref;
 
-use pi_curves::curve::frame::KeyFrameCurveValue;
 
-
-pub type TimeMS = KeyFrameCurveValue;
+/// 时间 - ms, 使用 f64 保证长时间运行的精度
+pub type TimeMS = f64;
 
 /// 动画启动和结束时的状态控制
 #[derive(Debug, Clone, Copy)]
@@ -23,4 +22,134 @@ impl EFillMode {
     pub const BACKWARDS: Self = EFillMode(2);
     /// 同时应用 Forwards Backwards
     pub const BOTH: Self = EFillMode(3);
-}
\ No newline at end of file
+}
+
+/// 动画组推进时间的方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ETimeStepMode {
+    /// 按帧率量化 - 默认
+    /// * 累计间隔达到 frame_ms * 0.75 才推进, 单次播放时长按 once_time - frame_ms * 0.5 计算
+    /// * 以推进前的时间计算进度, debug 模式下每次都推进
+    /// * 结果依赖于间隔的切分方式
+    #[default]
+    Quantized,
+    /// 连续时间 - 每次计算都推进, 以推进后的时间计算进度
+    Continuous,
+    /// 固定步长 - 累计间隔 按整数个步长逐步推进, 余量留到下次计算
+    /// * 每个步长都计算一次 循环 和 结束, 一次计算跨过多个步长时 与逐步推进的结果相同
+    /// * 步长 ms, 不大于 0 时同 Continuous
+    Fixed(TimeMS),
+}
+
+impl ETimeStepMode {
+    /// 相同的总间隔时间 是否得到相同结果
+    pub fn is_deterministic(&self) -> bool {
+        !matches!(self, ETimeStepMode::Quantized)
+    }
+}
+
+/// 单次计算的间隔时间过大时 的处理方式
+/// * 参数为 允许的最大间隔 ms
+/// * 只作用于间隔时间驱动的计算, 绝对时钟驱动时不生效
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ELargeDeltaPolicy {
+    /// 不处理 - 默认
+    #[default]
+    None,
+    /// 间隔时间 限制到最大值
+    Clamp(TimeMS),
+    /// 分为多步推进, 每步不超过最大值, 产生全部事件
+    /// * 步数超过 MAX_CATCH_UP_STEPS 时 按 Skip 处理
+    CatchUp(TimeMS),
+    /// 直接推进, 超过最大值时 不产生 帧事件 和 循环事件
+    /// * 区间进入离开事件 描述状态变化, 仍然产生
+    Skip(TimeMS),
+}
+
+/// ELargeDeltaPolicy::CatchUp 一次计算最多的推进步数
+pub const MAX_CATCH_UP_STEPS: u32 = 64;
+
+impl ELargeDeltaPolicy {
+    /// 依据间隔时间 得到 (推进步数, 每步时间, 是否屏蔽事件)
+    pub fn steps(&self, delta_ms: TimeMS) -> (u32, TimeMS, bool) {
+        match *self {
+            ELargeDeltaPolicy::Clamp(max) if max > 0. => (1, delta_ms.min(max), false),
+            ELargeDeltaPolicy::CatchUp(max) if max > 0. && delta_ms > max => {
+                let count = (delta_ms / max).ceil();
+                if count > MAX_CATCH_UP_STEPS as TimeMS {
+                    (1, delta_ms, true)
+                } else {
+                    (count as u32, delta_ms / count, false)
+                }
+            },
+            ELargeDeltaPolicy::Skip(max) => (1, delta_ms, delta_ms > max),
+            _ => (1, delta_ms, false),
+        }
+    }
+}
+/// 循环之间停顿时 保持的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EIterationHold {
+    /// 保持刚结束这次循环的最后一帧 - 默认
+    #[default]
+    LastFrame,
+    /// 保持下一次循环的第一帧
+    NextFirstFrame,
+}
+
+/// 循环之间的停顿
+/// * 实际停顿时长为 delay_ms + [0, jitter_ms) 内的随机值
+/// * 停顿结束 下一次循环开始时 才触发循环事件
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IterationDelay {
+    /// 停顿时长 - ms
+    pub delay_ms: TimeMS,
+    /// 随机增加的时长范围 - ms
+    pub jitter_ms: TimeMS,
+    /// 随机数种子 - 每次启动动画组时 以此重置随机序列
+    pub seed: u64,
+    /// 停顿时保持的状态
+    pub hold: EIterationHold,
+}
+
+impl IterationDelay {
+    pub fn new(delay_ms: TimeMS, hold: EIterationHold) -> Self {
+        Self { delay_ms, jitter_ms: 0., seed: 0, hold }
+    }
+    /// 设置随机增加的时长范围 和 随机数种子
+    pub fn jitter(mut self, jitter_ms: TimeMS, seed: u64) -> Self {
+        self.jitter_ms = jitter_ms;
+        self.seed = seed;
+        self
+    }
+    /// 是否有停顿
+    pub fn is_active(&self) -> bool {
+        self.delay_ms > 0. || self.jitter_ms > 0.
+    }
+}
+
+/// 可指定种子的随机数 - xorshift64*, 相同种子得到相同的序列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeededRandom(u64);
+
+impl SeededRandom {
+    pub fn new(seed: u64) -> Self {
+        // 状态不能为 0
+        Self(if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed })
+    }
+    /// [0, 1) 内的随机数
+    pub fn next_f64(&mut self) -> f64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for SeededRandom {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
@@ -1,4 +1,6 @@
-use pi_curves::curve::{FrameIndex, frame::KeyFrameCurveValue};
+use pi_curves::curve::{FrameIndex, FramePerSecond, frame::KeyFrameCurveValue};
+
+use crate::{loop_mode::AmountPass, base::TimeMS, error::EAnimationError};
 
 pub type FrameEventData = u16;
 
@@ -70,4 +72,223 @@ impl<D: Clone> CurveFrameEvent<D> {
             None
         }
     }
-}
\ No newline at end of file
+
+    /// 查询 各次播放的进度变化 经过的帧事件
+    /// * `passes` 通过 AnimationGroupRuntimeInfo.amount_passes 获得
+    /// * 结果按时间先后排列, 反向播放时帧事件按帧倒序
+    pub fn query_passes(
+        &self,
+        passes: &[AmountPass],
+    ) -> Option<Vec<D>> {
+        let mut result: Vec<D> = vec![];
+        for pass in passes {
+            let from = pass.from * self.total_frame;
+            let to = pass.to * self.total_frame;
+            if from <= to {
+                for (i, frame) in self.events.iter().enumerate() {
+                    let frame = *frame as KeyFrameCurveValue;
+                    if (from < frame || (pass.include_from && from == frame)) && frame <= to {
+                        result.push(self.datas[i].clone());
+                    }
+                }
+            } else {
+                for (i, frame) in self.events.iter().enumerate().rev() {
+                    let frame = *frame as KeyFrameCurveValue;
+                    if (frame < from || (pass.include_from && from == frame)) && to <= frame {
+                        result.push(self.datas[i].clone());
+                    }
+                }
+            }
+        }
+
+        if result.len() > 0 {
+            Some(result)
+        } else {
+            None
+        }
+    }
+}
+
+/// 一次计算跨过多次播放时 帧事件的补发方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EFrameEventCatchUp {
+    /// 经过的每次播放中的帧事件都触发
+    #[default]
+    All,
+    /// 同一帧事件数据 在一次计算中最多触发一次
+    Once,
+    /// 跳过被整体越过的播放, 只触发首尾两次播放中经过的帧事件
+    Skip,
+}
+
+/// 以时间描述的帧事件集合 - 挂载在动画组 或 Target动画 上
+/// * 事件位置记录为曲线时间轴上的秒数, 与动画组的播放速度和播放区间无关
+/// * 包含 时间点事件 和 区间事件, 区间事件以携带的数据作为区间的标识
+#[derive(Debug, Clone, Default)]
+pub struct TimeFrameEvents {
+    seconds: Vec<KeyFrameCurveValue>,
+    datas: Vec<FrameEventData>,
+    /// 区间事件 - (起始秒, 结束秒, 数据)
+    ranges: Vec<(KeyFrameCurveValue, KeyFrameCurveValue, FrameEventData)>,
+}
+
+impl TimeFrameEvents {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// 添加事件
+    /// * `second` 事件在曲线时间轴上的位置 - 秒
+    pub fn add(
+        &mut self,
+        second: KeyFrameCurveValue,
+        data: FrameEventData,
+    ) {
+        let index = self.seconds.partition_point(|v| *v <= second);
+        self.seconds.insert(index, second);
+        self.datas.insert(index, data);
+    }
+    /// 添加事件
+    /// * `frame` 事件所在帧
+    /// * `design_frame_per_second` 帧位置对应的设计帧率
+    pub fn add_frame(
+        &mut self,
+        frame: KeyFrameCurveValue,
+        design_frame_per_second: FramePerSecond,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        if design_frame_per_second == 0 {
+            Err(EAnimationError::AnimationFramePerSecondCannotZero)
+        } else {
+            self.add(frame / design_frame_per_second as KeyFrameCurveValue, data);
+            Ok(())
+        }
+    }
+    /// 添加事件
+    /// * `time_ms` 事件在曲线时间轴上的位置 - 毫秒
+    pub fn add_ms(
+        &mut self,
+        time_ms: TimeMS,
+        data: FrameEventData,
+    ) {
+        self.add((time_ms / 1000.0) as KeyFrameCurveValue, data);
+    }
+    /// 添加区间事件 - 动画组时间进入和离开区间时分别触发 RangeEnter RangeExit
+    /// * `start_second` `end_second` 区间在曲线时间轴上的位置 - 秒, 包含两端
+    pub fn add_range(
+        &mut self,
+        start_second: KeyFrameCurveValue,
+        end_second: KeyFrameCurveValue,
+        data: FrameEventData,
+    ) {
+        self.ranges.push((start_second.min(end_second), start_second.max(end_second), data));
+    }
+    /// 添加区间事件
+    /// * `start_frame` `end_frame` 区间的起止帧
+    /// * `design_frame_per_second` 帧位置对应的设计帧率
+    pub fn add_range_frame(
+        &mut self,
+        start_frame: KeyFrameCurveValue,
+        end_frame: KeyFrameCurveValue,
+        design_frame_per_second: FramePerSecond,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        if design_frame_per_second == 0 {
+            Err(EAnimationError::AnimationFramePerSecondCannotZero)
+        } else {
+            let fps = design_frame_per_second as KeyFrameCurveValue;
+            self.add_range(start_frame / fps, end_frame / fps, data);
+            Ok(())
+        }
+    }
+    /// 添加区间事件
+    /// * `start_ms` `end_ms` 区间在曲线时间轴上的位置 - 毫秒
+    pub fn add_range_ms(
+        &mut self,
+        start_ms: TimeMS,
+        end_ms: TimeMS,
+        data: FrameEventData,
+    ) {
+        self.add_range((start_ms / 1000.0) as KeyFrameCurveValue, (end_ms / 1000.0) as KeyFrameCurveValue, data);
+    }
+    /// 移除 携带指定数据的事件 和 区间事件
+    /// * 返回 是否有事件被移除
+    pub fn remove(
+        &mut self,
+        data: FrameEventData,
+    ) -> bool {
+        let count = self.datas.len() + self.ranges.len();
+        let mut index = 0;
+        while index < self.datas.len() {
+            if self.datas[index] == data {
+                self.seconds.remove(index);
+                self.datas.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        self.ranges.retain(|item| item.2 != data);
+        count != self.datas.len() + self.ranges.len()
+    }
+    pub fn clear(&mut self) {
+        self.seconds.clear();
+        self.datas.clear();
+        self.ranges.clear();
+    }
+    /// 时间点事件数目
+    pub fn len(&self) -> usize {
+        self.datas.len()
+    }
+    /// 区间事件数目
+    pub fn range_len(&self) -> usize {
+        self.ranges.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.datas.is_empty() && self.ranges.is_empty()
+    }
+    /// 收集 包含指定时刻的区间事件
+    pub fn collect_ranges_at(
+        &self,
+        second: KeyFrameCurveValue,
+        result: &mut Vec<FrameEventData>,
+    ) {
+        for (start, end, data) in self.ranges.iter() {
+            if *start <= second && second <= *end {
+                result.push(*data);
+            }
+        }
+    }
+    /// 收集 位于 [min, max] 内的区间边界
+    pub fn collect_range_bounds(
+        &self,
+        min: KeyFrameCurveValue,
+        max: KeyFrameCurveValue,
+        result: &mut Vec<KeyFrameCurveValue>,
+    ) {
+        for (start, end, _) in self.ranges.iter() {
+            if min <= *start && *start <= max {
+                result.push(*start);
+            }
+            if min <= *end && *end <= max {
+                result.push(*end);
+            }
+        }
+    }
+    /// 收集 曲线时间区间内的事件
+    /// * 区间为 (from_second, to_second], `include_from` 时包含 from_second
+    /// * `from_second` 大于 `to_second` 时为反向区间
+    pub fn collect(
+        &self,
+        from_second: KeyFrameCurveValue,
+        to_second: KeyFrameCurveValue,
+        include_from: bool,
+        result: &mut Vec<(KeyFrameCurveValue, FrameEventData)>,
+    ) {
+        let (min, max) = (from_second.min(to_second), from_second.max(to_second));
+        for (i, second) in self.seconds.iter().enumerate() {
+            let second = *second;
+            if min <= second && second <= max && (second != from_second || include_from) {
+                result.push((second, self.datas[i]));
+            }
+        }
+    }
+}
@@ -1,5 +1,5 @@
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EAnimationError {
     None = 0,
     NotFoundAttr,
@@ -14,4 +14,9 @@ pub enum EAnimationError {
     AnimationGroupNotPlaying,
     RuntimeInfoMapNotFindType,
     TargetIDNotRecordForTypeAnimationContext,
+    FrameCurveTypeNotFound,
+    TargetAnimationNotFound,
+    TimeChannelNotFound,
+    TimeChannelNameExists,
+    FrameCurveInUse,
 }
\ No newline at end of file
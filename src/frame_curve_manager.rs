@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{marker::PhantomData, sync::{Arc, Mutex}};
 
 use pi_curves::curve::{
     frame::{FrameDataValue, KeyFrameDataType},
@@ -10,15 +10,31 @@ use crate::error::EAnimationError;
 
 pub type FrameCurveInfoID = usize;
 
+/// 可在多个 TypeAnimationContext 间共享的 多数据类型帧曲线描述信息管理器
+pub type SharedFrameCurveInfoManager = Arc<Mutex<dyn TFrameCurveInfoManager + Send>>;
+/// 可共享的 某种数据类型的帧曲线数据池
+pub type SharedFrameCurvePool<T, D> = Arc<Mutex<dyn TFrameCurvePool<T, D> + Send>>;
+
+/// 某种数据类型的帧曲线描述信息管理器
+/// * 记录每个曲线被 AnimationInfo 引用的计数, 仍被引用的曲线不可 remove
 pub trait TTypeFrameCurveInfoManager {
     fn insert(&mut self, curve: FrameCurveInfo) -> FrameCurveInfoID;
     fn remove(&mut self, id: FrameCurveInfoID) -> Result<(), EAnimationError>;
     fn get(&self, id: FrameCurveInfoID) -> Result<FrameCurveInfo, EAnimationError>;
+    /// 更新曲线描述信息 - 曲线数据替换后使用
+    fn replace(&mut self, id: FrameCurveInfoID, curve: FrameCurveInfo) -> Result<(), EAnimationError>;
+    /// 增加引用计数, 返回增加后的计数
+    fn add_ref(&mut self, id: FrameCurveInfoID) -> Result<usize, EAnimationError>;
+    /// 减少引用计数, 返回减少后的计数
+    fn release(&mut self, id: FrameCurveInfoID) -> Result<usize, EAnimationError>;
+    fn ref_count(&self, id: FrameCurveInfoID) -> usize;
 }
 
+/// 多数据类型的帧曲线描述信息管理器
+/// * 按 KeyFrameDataType 区分, 使用前需 add_type
 pub trait TFrameCurveInfoManager {
     fn add_type(&mut self, ty: KeyFrameDataType) -> Result<(), EAnimationError>;
-    fn insert(&mut self, ty: KeyFrameDataType, curve: FrameCurveInfo) -> FrameCurveInfoID;
+    fn insert(&mut self, ty: KeyFrameDataType, curve: FrameCurveInfo) -> Result<FrameCurveInfoID, EAnimationError>;
     fn remove(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID)
         -> Result<(), EAnimationError>;
     fn get(
@@ -26,23 +42,30 @@ pub trait TFrameCurveInfoManager {
         ty: KeyFrameDataType,
         id: FrameCurveInfoID,
     ) -> Result<FrameCurveInfo, EAnimationError>;
+    fn replace(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID, curve: FrameCurveInfo) -> Result<(), EAnimationError>;
+    fn add_ref(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> Result<usize, EAnimationError>;
+    fn release(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> Result<usize, EAnimationError>;
+    fn ref_count(&self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> usize;
 }
 
-pub trait TFrameCurvePool<T: FrameDataValue> {
-    fn insert(&mut self, id: FrameCurveInfoID, curve: Arc<FrameCurve<T>>);
+/// 某种数据类型的帧曲线数据池
+/// * 曲线 ID 由对应数据类型的帧曲线描述信息管理器分配
+pub trait TFrameCurvePool<T: FrameDataValue, D: AsRef<FrameCurve<T>> = Arc<FrameCurve<T>>> {
+    fn insert(&mut self, id: FrameCurveInfoID, curve: D);
     fn remove(&mut self, id: FrameCurveInfoID) -> Result<(), EAnimationError>;
-    fn get(&self, id: FrameCurveInfoID) -> Result<Arc<FrameCurve<T>>, EAnimationError>;
+    fn get(&self, id: FrameCurveInfoID) -> Result<&D, EAnimationError>;
 }
 
 /// 针对某种数据类型对应的帧曲线描述信息管理器
 /// * 每个曲线的 ID 一旦分配便不会变化
 /// * 每个曲线的 数据是不可变的
-/// * 通过 add 接口获得 ID
-/// * 通过 del 接口删除 曲线
+/// * 通过 insert 接口获得 ID
+/// * 通过 remove 接口删除 曲线, 删除后 ID 可被再次分配; 仍被引用的曲线删除失败
 pub struct TypeFrameCurveInfoManager {
     id_pool: Vec<FrameCurveInfoID>,
     counter: FrameCurveInfoID,
-    curve_infos: Vec<FrameCurveInfo>,
+    curve_infos: Vec<Option<FrameCurveInfo>>,
+    curve_refs: Vec<usize>,
 }
 
 impl TypeFrameCurveInfoManager {
@@ -51,18 +74,17 @@ impl TypeFrameCurveInfoManager {
             id_pool: vec![],
             counter: 0,
             curve_infos: vec![],
+            curve_refs: vec![],
         }
     }
 }
 
 impl TTypeFrameCurveInfoManager for TypeFrameCurveInfoManager {
     fn insert(&mut self, curve: FrameCurveInfo) -> FrameCurveInfoID {
-        let id = match self.id_pool.pop() {
+        match self.id_pool.pop() {
             Some(id) => {
-                let info = self.curve_infos.get_mut(id).unwrap();
-                info.max_frame = curve.max_frame;
-                info.min_frame = curve.min_frame;
-                info.design_frame_per_second = curve.design_frame_per_second;
+                self.curve_infos[id] = Some(curve);
+                self.curve_refs[id] = 0;
 
                 id
             }
@@ -70,28 +92,171 @@ impl TTypeFrameCurveInfoManager for TypeFrameCurveInfoManager {
                 let id = self.counter;
                 self.counter += 1;
 
-                self.curve_infos.push(curve);
+                self.curve_infos.push(Some(curve));
+                self.curve_refs.push(0);
                 id
             }
-        };
-
-        id
+        }
     }
     fn remove(&mut self, id: FrameCurveInfoID) -> Result<(), EAnimationError> {
-        if id < self.counter {
-            // 回收 ID
-            if !self.id_pool.contains(&id) {
+        if self.ref_count(id) > 0 {
+            return Err(EAnimationError::FrameCurveInUse);
+        }
+        match self.curve_infos.get_mut(id) {
+            Some(info) if info.is_some() => {
+                *info = None;
+                // 回收 ID
                 self.id_pool.push(id);
+                Ok(())
             }
-            Ok(())
-        } else {
-            Err(EAnimationError::FrameCurveNotFound)
+            _ => Err(EAnimationError::FrameCurveNotFound),
         }
     }
     fn get(&self, id: FrameCurveInfoID) -> Result<FrameCurveInfo, EAnimationError> {
         match self.curve_infos.get(id) {
-            Some(v) => Ok(*v),
-            None => Err(EAnimationError::FrameCurveNotFound),
+            Some(Some(v)) => Ok(*v),
+            _ => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    fn replace(&mut self, id: FrameCurveInfoID, curve: FrameCurveInfo) -> Result<(), EAnimationError> {
+        match self.curve_infos.get_mut(id) {
+            Some(Some(info)) => {
+                *info = curve;
+                Ok(())
+            }
+            _ => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    fn add_ref(&mut self, id: FrameCurveInfoID) -> Result<usize, EAnimationError> {
+        match (self.curve_infos.get(id), self.curve_refs.get_mut(id)) {
+            (Some(Some(_)), Some(count)) => {
+                *count += 1;
+                Ok(*count)
+            }
+            _ => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    fn release(&mut self, id: FrameCurveInfoID) -> Result<usize, EAnimationError> {
+        match (self.curve_infos.get(id), self.curve_refs.get_mut(id)) {
+            (Some(Some(_)), Some(count)) if *count > 0 => {
+                *count -= 1;
+                Ok(*count)
+            }
+            _ => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    fn ref_count(&self, id: FrameCurveInfoID) -> usize {
+        match (self.curve_infos.get(id), self.curve_refs.get(id)) {
+            (Some(Some(_)), Some(count)) => *count,
+            _ => 0,
+        }
+    }
+}
+
+/// 多数据类型的帧曲线描述信息管理器
+/// * 每种数据类型 对应一个 TypeFrameCurveInfoManager
+/// * 可在多个 TypeAnimationContext 间共享
+#[derive(Default)]
+pub struct FrameCurveInfoManager {
+    list: Vec<Option<TypeFrameCurveInfoManager>>,
+}
+
+impl TFrameCurveInfoManager for FrameCurveInfoManager {
+    fn add_type(&mut self, ty: KeyFrameDataType) -> Result<(), EAnimationError> {
+        if ty >= self.list.len() {
+            for _ in self.list.len()..ty + 1 {
+                self.list.push(None);
+            }
+        }
+        if self.list[ty].is_none() {
+            self.list[ty] = Some(TypeFrameCurveInfoManager::default());
+        }
+        Ok(())
+    }
+    fn insert(&mut self, ty: KeyFrameDataType, curve: FrameCurveInfo) -> Result<FrameCurveInfoID, EAnimationError> {
+        match self.list.get_mut(ty) {
+            Some(Some(mgr)) => Ok(mgr.insert(curve)),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn remove(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID)
+        -> Result<(), EAnimationError> {
+        match self.list.get_mut(ty) {
+            Some(Some(mgr)) => mgr.remove(id),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn get(
+        &self,
+        ty: KeyFrameDataType,
+        id: FrameCurveInfoID,
+    ) -> Result<FrameCurveInfo, EAnimationError> {
+        match self.list.get(ty) {
+            Some(Some(mgr)) => mgr.get(id),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn replace(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID, curve: FrameCurveInfo) -> Result<(), EAnimationError> {
+        match self.list.get_mut(ty) {
+            Some(Some(mgr)) => mgr.replace(id, curve),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn add_ref(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> Result<usize, EAnimationError> {
+        match self.list.get_mut(ty) {
+            Some(Some(mgr)) => mgr.add_ref(id),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn release(&mut self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> Result<usize, EAnimationError> {
+        match self.list.get_mut(ty) {
+            Some(Some(mgr)) => mgr.release(id),
+            _ => Err(EAnimationError::FrameCurveTypeNotFound),
+        }
+    }
+    fn ref_count(&self, ty: KeyFrameDataType, id: FrameCurveInfoID) -> usize {
+        match self.list.get(ty) {
+            Some(Some(mgr)) => mgr.ref_count(id),
+            _ => 0,
+        }
+    }
+}
+
+/// 某种数据类型的帧曲线数据池
+/// * 按曲线 ID 保存曲线数据, ID 由 FrameCurveInfoManager 分配
+pub struct FrameCurvePool<T: FrameDataValue, D: AsRef<FrameCurve<T>> = Arc<FrameCurve<T>>> {
+    curves: Vec<Option<D>>,
+    pd: PhantomData<T>,
+}
+
+impl<T: FrameDataValue, D: AsRef<FrameCurve<T>>> Default for FrameCurvePool<T, D> {
+    fn default() -> Self {
+        Self { curves: vec![], pd: PhantomData }
+    }
+}
+
+impl<T: FrameDataValue, D: AsRef<FrameCurve<T>>> TFrameCurvePool<T, D> for FrameCurvePool<T, D> {
+    fn insert(&mut self, id: FrameCurveInfoID, curve: D) {
+        if id >= self.curves.len() {
+            for _ in self.curves.len()..id + 1 {
+                self.curves.push(None);
+            }
+        }
+        self.curves[id] = Some(curve);
+    }
+    fn remove(&mut self, id: FrameCurveInfoID) -> Result<(), EAnimationError> {
+        match self.curves.get_mut(id) {
+            Some(curve) if curve.is_some() => {
+                *curve = None;
+                Ok(())
+            }
+            _ => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    fn get(&self, id: FrameCurveInfoID) -> Result<&D, EAnimationError> {
+        match self.curves.get(id) {
+            Some(Some(curve)) => Ok(curve),
+            _ => Err(EAnimationError::FrameCurveNotFound),
         }
     }
 }
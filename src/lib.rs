@@ -9,9 +9,15 @@ pub mod frame_curve_manager;
 pub mod runtime_info;
 pub mod target_animation;
 pub mod animation_listener;
+pub mod animation_event_dispatcher;
+pub mod animation_command;
+pub mod animation_future;
 pub mod curve_frame_event;
 pub mod amount;
 pub mod animation_result_pool;
 pub mod animation_group_manager;
 pub mod base;
+pub mod time_channel;
+pub mod speed_curve;
 pub mod type_animation_context;
+pub mod type_animation_registry;
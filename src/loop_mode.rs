@@ -1,4 +1,6 @@
 use pi_curves::curve::{frame::KeyFrameCurveValue};
+
+use crate::base::TimeMS;
 use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -25,7 +27,195 @@ impl Default for ELoopMode {
     }
 }
 
-pub fn get_amount_calc(mode: ELoopMode) -> fn(KeyFrameCurveValue, KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+/// 动画进度在一次播放内的一段变化 - 用于查询该段经过的帧事件
+/// * 往复循环中 往 和 复 各算一次播放
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmountPass {
+    /// 第几次播放 - 从 0 开始
+    pub index: u32,
+    /// 起始进度 0~1
+    pub from: KeyFrameCurveValue,
+    /// 结束进度 0~1, 反向播放时 小于 from
+    pub to: KeyFrameCurveValue,
+    /// 是否包含起始进度位置
+    pub include_from: bool,
+}
+
+/// 循环模式下 最多播放次数 - None 无限循环
+pub fn get_pass_count(mode: ELoopMode) -> Option<u32> {
+    match mode {
+        ELoopMode::Not => Some(1),
+        ELoopMode::Positive(count) => count.map(|v| v.max(1)),
+        ELoopMode::Opposite(count) => count.map(|v| v.max(1)),
+        ELoopMode::PositivePly(count) => count.map(|v| v.max(1) * 2),
+        ELoopMode::OppositePly(count) => count.map(|v| v.max(1) * 2),
+    }
+}
+
+/// 循环模式下 每次循环包含的播放次数 - 往复循环为 2
+pub fn get_loop_passes(mode: ELoopMode) -> u32 {
+    match mode {
+        ELoopMode::PositivePly(_) => 2,
+        ELoopMode::OppositePly(_) => 2,
+        _ => 1,
+    }
+}
+
+/// 循环模式 - 由运行时间计算进度
+/// * 内置的 ELoopMode 为其实现, 可自定义实现 如 转向时带缓动的往复循环
+/// * 播放次数 仍由 ELoopMode 或 循环范围 指定
+pub trait TLoopMode: Send + Sync {
+    /// (单次播放时间, 运行时间) -> (本次播放内的进度, 已完成的循环次数)
+    /// * 运行时间 已由调用方按循环范围换算, 从第一次循环起点开始, 结束位置不在循环边界上时 限制在结束位置, 参见 IterationRange.time
+    fn amount(&self, once_time: TimeMS, time: TimeMS) -> (KeyFrameCurveValue, u32);
+    /// 每次循环包含的播放次数
+    fn loop_passes(&self) -> u32 {
+        1
+    }
+    /// 第 index 次播放 是否为正向 - 播放方向只由此确定
+    /// * 用于 当前播放方向, 延时 结束 停顿时的状态 和 帧事件查询
+    fn is_positive_pass(&self, index: u32) -> bool;
+}
+
+impl TLoopMode for ELoopMode {
+    fn amount(&self, once_time: TimeMS, time: TimeMS) -> (KeyFrameCurveValue, u32) {
+        get_amount_calc(*self)(once_time, time)
+    }
+    fn loop_passes(&self) -> u32 {
+        get_loop_passes(*self)
+    }
+    fn is_positive_pass(&self, index: u32) -> bool {
+        is_positive_pass(*self, index)
+    }
+}
+
+/// 以循环次数计的 位置 或 次数 - 可为小数
+pub type IterationCount = f64;
+
+/// 循环范围 - 对应 CSS 的 animation-iteration-start 和 animation-iteration-count
+/// * 以循环次数为单位, 往复循环中 往复一次为一次循环
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct IterationRange {
+    /// 起始位置 - 如 0.3 表示从第一次循环的 30% 处开始
+    pub start: IterationCount,
+    /// 循环次数 - 可为小数
+    /// * 设置时 None 表示使用循环模式的次数, 补全后 None 表示无限循环
+    pub count: Option<IterationCount>,
+}
+
+impl IterationRange {
+    pub fn new(start: IterationCount, count: Option<IterationCount>) -> Self {
+        Self { start, count }
+    }
+    /// 以循环模式的次数 补全未指定的循环次数
+    /// * ELoopMode 指定的播放次数 按实际使用的循环模式 换算为循环次数
+    pub fn resolve(&self, mode: ELoopMode, loop_calc: &dyn TLoopMode) -> Self {
+        let count = match self.count {
+            Some(count) => Some(count.max(0.)),
+            None => get_pass_count(mode).map(|count| count as IterationCount / loop_calc.loop_passes().max(1) as IterationCount),
+        };
+        Self { start: self.start.max(0.), count }
+    }
+    /// 结束位置 - 以循环次数计
+    pub fn end(&self) -> Option<IterationCount> {
+        self.count.map(|count| self.start + count)
+    }
+    /// 运行时间 对应的位置 - 以循环次数计
+    /// * `loop_time` 一次循环的时间
+    pub fn position(&self, loop_time: TimeMS, time: TimeMS) -> IterationCount {
+        time / loop_time + self.start
+    }
+    /// 运行时间 所在的播放 - 从 0 开始
+    /// * `once_time` 一次播放的时间
+    pub fn pass_index(&self, once_time: TimeMS, loop_passes: u32, time: TimeMS) -> u32 {
+        (self.time(once_time * loop_passes as TimeMS, time) / once_time).floor() as u32
+    }
+    /// 运行时间 换算为 从第一次循环起点开始的时间
+    /// * 结束位置不在循环边界上时 限制在结束位置
+    pub fn time(&self, loop_time: TimeMS, time: TimeMS) -> TimeMS {
+        let time = time + self.start * loop_time;
+        match self.end() {
+            Some(end) if end.fract() != 0. => time.min(end * loop_time),
+            _ => time,
+        }
+    }
+}
+
+/// 第 index 次播放 是否为正向
+pub fn is_positive_pass(mode: ELoopMode, index: u32) -> bool {
+    match mode {
+        ELoopMode::Not => true,
+        ELoopMode::Positive(_) => true,
+        ELoopMode::Opposite(_) => false,
+        ELoopMode::PositivePly(_) => index & 1 == 0,
+        ELoopMode::OppositePly(_) => index & 1 == 1,
+    }
+}
+
+/// 计算 运行时间从 last_time 到 time 经过的各次播放的进度变化, 按时间先后排列
+/// * `iteration` 补全循环次数后的循环范围
+/// * `once_time` 一次播放的时间
+/// * `include_last` 是否包含 last_time 时刻 - 动画启动的第一次计算时为 true
+/// * 超出循环范围的时间 被忽略
+pub fn get_amount_passes(
+    mode: &dyn TLoopMode,
+    iteration: &IterationRange,
+    once_time: TimeMS,
+    last_time: TimeMS,
+    time: TimeMS,
+    include_last: bool,
+) -> Vec<AmountPass> {
+    let mut result = vec![];
+    if once_time <= 0. || time < last_time {
+        return result;
+    }
+
+    let loop_time = once_time * mode.loop_passes() as TimeMS;
+    let (last_time, time) = (last_time + iteration.start * loop_time, time + iteration.start * loop_time);
+    let limit = iteration.end().map(|end| end * loop_time);
+    let (last_time, time) = match limit {
+        Some(limit) => (last_time.min(limit), time.min(limit)),
+        None => (last_time, time),
+    };
+
+    let first = (last_time / once_time).floor() as u32;
+    let mut last = (time / once_time).floor() as u32;
+    if let Some(limit) = limit {
+        last = last.min(((limit / once_time).ceil() as u32).max(1) - 1);
+    }
+
+    let mut index = first;
+    while index <= last {
+        let start = index as TimeMS * once_time;
+        let end = start + once_time;
+        let pass_from = last_time.max(start);
+        let pass_to = time.min(end);
+        // 上一次计算已包含 last_time 时刻
+        let include_from = pass_from > last_time || include_last;
+
+        if pass_from < pass_to || include_from {
+            let amount_from = if pass_from >= end { 1. } else { ((pass_from - start) / once_time) as KeyFrameCurveValue };
+            let amount_to = if pass_to >= end { 1. } else { ((pass_to - start) / once_time) as KeyFrameCurveValue };
+            let (from, to) = if mode.is_positive_pass(index) {
+                (amount_from, amount_to)
+            } else {
+                (1. - amount_from, 1. - amount_to)
+            };
+            result.push(AmountPass { index, from, to, include_from });
+        }
+
+        index += 1;
+    }
+
+    result
+}
+
+/// 进度计算 - (单次播放时间, 运行时间) -> (本次播放内的进度, 已完成的循环次数)
+/// * 时间使用 TimeMS 计算, 长时间运行后 进度仍保持精度
+/// * 运行时间 为按循环范围换算后的时间, 参见 TLoopMode.amount
+pub type AmountCalcFn = fn(TimeMS, TimeMS) -> (KeyFrameCurveValue, u32);
+
+pub fn get_amount_calc(mode: ELoopMode) -> AmountCalcFn {
     match mode {
         ELoopMode::Not => amount_not,
         ELoopMode::Positive(_) => amount_positive,
@@ -35,52 +225,52 @@ pub fn get_amount_calc(mode: ELoopMode) -> fn(KeyFrameCurveValue, KeyFrameCurveV
     }
 }
 
-fn amount_not(once_time: KeyFrameCurveValue, delay_ms: KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+fn amount_not(once_time: TimeMS, delay_ms: TimeMS) -> (KeyFrameCurveValue, u32) {
     let loop_count = (delay_ms / once_time).floor();
-    let delay_ms = KeyFrameCurveValue::max(0., KeyFrameCurveValue::min(once_time, delay_ms));
-    let amount = (delay_ms as f32 / once_time as f32) as KeyFrameCurveValue;
+    let delay_ms = TimeMS::max(0., TimeMS::min(once_time, delay_ms));
+    let amount = (delay_ms / once_time) as KeyFrameCurveValue;
 
     (amount, loop_count as u32)
 }
 
-fn amount_positive(once_time: KeyFrameCurveValue, delay_ms: KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+fn amount_positive(once_time: TimeMS, delay_ms: TimeMS) -> (KeyFrameCurveValue, u32) {
     let loop_count = (delay_ms / once_time).floor();
 
-    let amount = ((delay_ms as f32 - loop_count * once_time) / once_time as f32) as KeyFrameCurveValue;
+    let amount = ((delay_ms - loop_count * once_time) / once_time) as KeyFrameCurveValue;
 
     (amount, loop_count as u32)
 }
 
-fn amount_opposite(once_time: KeyFrameCurveValue, delay_ms: KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+fn amount_opposite(once_time: TimeMS, delay_ms: TimeMS) -> (KeyFrameCurveValue, u32) {
     let loop_count = (delay_ms / once_time).floor();
 
-    let amount = (1.0 - (delay_ms as f32 - loop_count * once_time) / once_time as f32) as KeyFrameCurveValue;
+    let amount = (1.0 - (delay_ms - loop_count * once_time) / once_time) as KeyFrameCurveValue;
 
     (amount, loop_count as u32)
 }
 
-fn amount_positive_ply(once_time: KeyFrameCurveValue, delay_ms: KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+fn amount_positive_ply(once_time: TimeMS, delay_ms: TimeMS) -> (KeyFrameCurveValue, u32) {
     let loop_count = (delay_ms / once_time).floor() as i32;
     let result_count = loop_count / 2;
 
     let amount = if loop_count != result_count * 2  {
-        1.0 - (delay_ms as KeyFrameCurveValue - loop_count as KeyFrameCurveValue * once_time) / once_time as KeyFrameCurveValue
+        1.0 - (delay_ms - loop_count as TimeMS * once_time) / once_time
     } else {
-        (delay_ms as KeyFrameCurveValue - loop_count as KeyFrameCurveValue * once_time) / once_time as KeyFrameCurveValue
+        (delay_ms - loop_count as TimeMS * once_time) / once_time
     };
 
-    (amount, result_count as u32)
+    (amount as KeyFrameCurveValue, result_count as u32)
 }
 
-fn amount_opposite_ply(once_time: KeyFrameCurveValue, delay_ms: KeyFrameCurveValue) -> (KeyFrameCurveValue, u32) {
+fn amount_opposite_ply(once_time: TimeMS, delay_ms: TimeMS) -> (KeyFrameCurveValue, u32) {
     let loop_count = (delay_ms / once_time).floor() as i32;
     let result_count = loop_count / 2;
 
     let amount = if loop_count != result_count * 2 {
-        (delay_ms - loop_count as KeyFrameCurveValue * once_time) / once_time
+        (delay_ms - loop_count as TimeMS * once_time) / once_time
     } else {
-        1.0 - (delay_ms - loop_count as KeyFrameCurveValue * once_time) / once_time
+        1.0 - (delay_ms - loop_count as TimeMS * once_time) / once_time
     };
 
-    (amount, result_count as u32)
-}
\ No newline at end of file
+    (amount as KeyFrameCurveValue, result_count as u32)
+}
@@ -0,0 +1,111 @@
+use pi_curves::curve::frame::KeyFrameCurveValue;
+
+use crate::base::TimeMS;
+
+/// 播放速度曲线 - 动画组启动 (延时结束) 后经过的时间 到 速度倍率
+/// * 关键点之间线性插值, 第一个关键点之前 和 最后一个关键点之后 保持端点速度
+/// * 速度小于 0 时按 0 计算
+/// * 动画组的运行时间 为速度曲线的积分 乘以动画组 speed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpeedCurve {
+    /// (时间 ms, 速度倍率), 按时间排序
+    keys: Vec<(TimeMS, KeyFrameCurveValue)>,
+}
+
+impl SpeedCurve {
+    /// 以关键点创建 - 没有关键点时 速度恒为 1
+    /// * 相邻关键点的速度 一正一负时 在速度为 0 处插入关键点, 使各段的速度不跨过 0
+    pub fn new(mut keys: Vec<(TimeMS, KeyFrameCurveValue)>) -> Self {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut result: Vec<(TimeMS, KeyFrameCurveValue)> = Vec::with_capacity(keys.len());
+        for key in keys {
+            if let Some(last) = result.last().copied() {
+                if (last.1 > 0. && key.1 < 0.) || (last.1 < 0. && key.1 > 0.) {
+                    let t = last.1 as TimeMS / (last.1 - key.1) as TimeMS;
+                    result.push((last.0 + (key.0 - last.0) * t, 0.));
+                }
+            }
+            result.push(key);
+        }
+        Self { keys: result }
+    }
+    /// 在前 `duration_ms` 内 速度从 `from` 线性变化到 `to`
+    pub fn ramp(from: KeyFrameCurveValue, to: KeyFrameCurveValue, duration_ms: TimeMS) -> Self {
+        Self::new(vec![(0., from), (duration_ms, to)])
+    }
+    pub fn keys(&self) -> &[(TimeMS, KeyFrameCurveValue)] {
+        &self.keys
+    }
+    /// 指定时间的速度倍率
+    pub fn speed_at(&self, time_ms: TimeMS) -> KeyFrameCurveValue {
+        let speed = match (self.keys.first(), self.keys.last()) {
+            (Some(first), Some(last)) => {
+                if time_ms <= first.0 {
+                    first.1
+                } else if time_ms >= last.0 {
+                    last.1
+                } else {
+                    let index = self.keys.iter().position(|key| key.0 > time_ms).unwrap_or(self.keys.len() - 1);
+                    let (a, b) = (self.keys[index - 1], self.keys[index]);
+                    let t = ((time_ms - a.0) / (b.0 - a.0)) as KeyFrameCurveValue;
+                    a.1 + (b.1 - a.1) * t
+                }
+            },
+            _ => 1.,
+        };
+        speed.max(0.)
+    }
+    /// 从 0 到 `time_ms` 的速度积分 - 即以速度 1 计的运行时间
+    pub fn integral(&self, time_ms: TimeMS) -> TimeMS {
+        if time_ms <= 0. {
+            return 0.;
+        }
+        let mut result = 0.;
+        let mut last = (0., self.speed_at(0.) as TimeMS);
+        for key in self.keys.iter() {
+            if key.0 <= 0. {
+                continue;
+            }
+            if key.0 >= time_ms {
+                break;
+            }
+            let speed = self.speed_at(key.0) as TimeMS;
+            result += (key.0 - last.0) * (last.1 + speed) * 0.5;
+            last = (key.0, speed);
+        }
+        result + (time_ms - last.0) * (last.1 + self.speed_at(time_ms) as TimeMS) * 0.5
+    }
+    /// 积分达到 `distance_ms` 需要的时间 - 速度最终为 0 而无法达到时 返回 None
+    pub fn inverse(&self, distance_ms: TimeMS) -> Option<TimeMS> {
+        if distance_ms <= 0. {
+            return Some(0.);
+        }
+        let mut result = 0.;
+        let mut last = (0., self.speed_at(0.) as TimeMS);
+        for key in self.keys.iter() {
+            if key.0 <= 0. {
+                continue;
+            }
+            let speed = self.speed_at(key.0) as TimeMS;
+            let area = (key.0 - last.0) * (last.1 + speed) * 0.5;
+            if result + area >= distance_ms {
+                let remain = distance_ms - result;
+                let slope = (speed - last.1) / (key.0 - last.0);
+                // v0 * x + slope * x * x / 2 = remain
+                let x = if slope.abs() < 1e-12 {
+                    remain / last.1
+                } else {
+                    (-last.1 + (last.1 * last.1 + 2. * slope * remain).max(0.).sqrt()) / slope
+                };
+                return Some(last.0 + x);
+            }
+            result += area;
+            last = (key.0, speed);
+        }
+        if last.1 > 0. {
+            Some(last.0 + (distance_ms - result) / last.1)
+        } else {
+            None
+        }
+    }
+}
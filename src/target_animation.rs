@@ -1,9 +1,29 @@
-use crate::{animation::{AnimationInfo}};
+use crate::{animation::{AnimationInfo}, curve_frame_event::TimeFrameEvents};
 
 /// Target动画 数据结构
 /// * 关联动画目标 和 动画
+/// * 可挂载只属于该 Target动画 的帧事件, 随 Target动画 移除
+/// * 通过 new 创建
 #[derive(Debug)]
 pub struct TargetAnimation<T> {
     pub target: T,
     pub animation: AnimationInfo,
-}
\ No newline at end of file
+    frame_events: TimeFrameEvents,
+}
+
+impl<T> TargetAnimation<T> {
+    pub fn new(target: T, animation: AnimationInfo) -> Self {
+        Self {
+            target,
+            animation,
+            frame_events: TimeFrameEvents::default(),
+        }
+    }
+    /// 挂载的帧事件
+    pub fn frame_events(&self) -> &TimeFrameEvents {
+        &self.frame_events
+    }
+    pub fn frame_events_mut(&mut self) -> &mut TimeFrameEvents {
+        &mut self.frame_events
+    }
+}
@@ -0,0 +1,139 @@
+use pi_hash::XHashMap;
+use pi_slotmap::{SlotMap, DefaultKey};
+
+use crate::{base::TimeMS, error::EAnimationError};
+
+pub type TimeChannelID = DefaultKey;
+
+/// 时间通道 - 分组控制动画组的时间缩放 和 暂停
+/// * 可挂在父通道下, 实际缩放为 自身 与 各级父通道 缩放的乘积
+/// * 自身 或 任一父通道 暂停时, 通道内的动画组时间不推进
+#[derive(Debug, Clone)]
+pub struct TimeChannel {
+    name: String,
+    parent: Option<TimeChannelID>,
+    /// 时间缩放
+    pub scale: f32,
+    /// 是否暂停
+    pub paused: bool,
+    /// 绝对时钟驱动时 通道的时钟 - ms
+    clock_ms: TimeMS,
+    /// 绝对时钟驱动时 通道上次计算的时钟 - ms
+    last_clock_ms: Option<TimeMS>,
+}
+
+impl TimeChannel {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn parent(&self) -> Option<TimeChannelID> {
+        self.parent
+    }
+}
+
+/// 时间通道集合 - 以名称查找通道
+#[derive(Default)]
+pub struct TimeChannels {
+    channels: SlotMap<DefaultKey, TimeChannel>,
+    names: XHashMap<String, TimeChannelID>,
+    /// 绝对时钟驱动时 新建通道的初始时钟
+    clock_ms: TimeMS,
+}
+
+impl TimeChannels {
+    /// 创建时间通道
+    /// * `parent` 父通道 - None 时直接受 context 的 time_scale 控制
+    pub fn create(&mut self, name: &str, parent: Option<TimeChannelID>) -> Result<TimeChannelID, EAnimationError> {
+        if self.names.contains_key(name) {
+            return Err(EAnimationError::TimeChannelNameExists);
+        }
+        if let Some(parent) = parent {
+            if !self.channels.contains_key(parent) {
+                return Err(EAnimationError::TimeChannelNotFound);
+            }
+        }
+        let id = self.channels.insert(TimeChannel {
+            name: String::from(name),
+            parent,
+            scale: 1.,
+            paused: false,
+            clock_ms: self.clock_ms,
+            last_clock_ms: None,
+        });
+        self.names.insert(String::from(name), id);
+        Ok(id)
+    }
+    /// 移除时间通道 - 子通道 挂到被移除通道的父通道下
+    /// * 返回被移除通道的父通道
+    pub fn remove(&mut self, id: TimeChannelID) -> Result<Option<TimeChannelID>, EAnimationError> {
+        match self.channels.remove(id) {
+            Some(channel) => {
+                self.names.remove(&channel.name);
+                for (_, item) in self.channels.iter_mut() {
+                    if item.parent == Some(id) {
+                        item.parent = channel.parent;
+                    }
+                }
+                Ok(channel.parent)
+            },
+            None => Err(EAnimationError::TimeChannelNotFound),
+        }
+    }
+    /// 以名称查找时间通道
+    pub fn id(&self, name: &str) -> Option<TimeChannelID> {
+        self.names.get(name).copied()
+    }
+    pub fn get(&self, id: TimeChannelID) -> Option<&TimeChannel> {
+        self.channels.get(id)
+    }
+    pub fn get_mut(&mut self, id: TimeChannelID) -> Option<&mut TimeChannel> {
+        self.channels.get_mut(id)
+    }
+    pub fn len(&self) -> usize {
+        self.channels.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+    /// 通道的实际时间缩放 - 通道不存在时为 1
+    pub fn effective_scale(&self, id: TimeChannelID) -> f32 {
+        let mut scale = 1.;
+        let mut current = Some(id);
+        while let Some(id) = current {
+            match self.channels.get(id) {
+                Some(channel) => {
+                    if channel.paused {
+                        return 0.;
+                    }
+                    scale *= channel.scale;
+                    current = channel.parent;
+                },
+                None => break,
+            }
+        }
+        scale
+    }
+    /// 绝对时钟驱动时 通道的 (当前时钟, 上次计算的时钟)
+    pub fn clock(&self, id: TimeChannelID) -> Option<(TimeMS, Option<TimeMS>)> {
+        self.channels.get(id).map(|channel| (channel.clock_ms, channel.last_clock_ms))
+    }
+    /// 绝对时钟驱动 首次计算时 设置所有通道的时钟
+    pub(crate) fn reset_clock(&mut self, clock_ms: TimeMS) {
+        self.clock_ms = clock_ms;
+        for (_, channel) in self.channels.iter_mut() {
+            channel.clock_ms = clock_ms;
+            channel.last_clock_ms = None;
+        }
+    }
+    /// 绝对时钟驱动时 按 context 时钟的变化 推进各通道时钟
+    pub(crate) fn advance_clock(&mut self, clock_ms: TimeMS, delta_ms: TimeMS) {
+        self.clock_ms = clock_ms;
+        let scales: Vec<(TimeChannelID, f32)> = self.channels.keys().map(|id| (id, self.effective_scale(id))).collect();
+        for (id, scale) in scales {
+            if let Some(channel) = self.channels.get_mut(id) {
+                channel.last_clock_ms = Some(channel.clock_ms);
+                channel.clock_ms += delta_ms * scale as TimeMS;
+            }
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::{marker::PhantomData, hash::Hash};
+use std::{marker::PhantomData, hash::Hash, sync::{Arc, mpsc::Sender}, time::Duration};
 
 use pi_curves::curve::{
     frame::{FrameDataValue, KeyFrameCurveValue, KeyFrameDataType},
@@ -6,6 +6,7 @@ use pi_curves::curve::{
     FramePerSecond,
 };
 
+use pi_hash::XHashMap;
 use pi_slotmap::{DefaultKey, SecondaryMap};
 
 use crate::{
@@ -13,28 +14,51 @@ use crate::{
     animation::AnimationInfo,
     animation_group::{AnimationGroupID, AnimationGroupRuntimeInfo, AnimationGroup},
     animation_group_manager::AnimationGroupManager,
-    animation_listener::{AnimationListener, EAnimationEvent},
+    animation_listener::{AnimationListener, AnimationEvent, EAnimationEvent, OnAnimationEvent, EListenerErrorPolicy},
+    animation_command::{AnimationCommands, AnimationStartParam, EAnimationCommand},
+    animation_future::{AnimationWaiters, AnimationWait, EAnimationEndReason},
+    animation_event_dispatcher::{AnimationEventDispatcher, AnimationListenerID},
     animation_result_pool::{TypeAnimationResultPool, AnimeResult},
-    curve_frame_event::CurveFrameEvent,
+    curve_frame_event::{CurveFrameEvent, FrameEventData, EFrameEventCatchUp},
+    time_channel::{TimeChannels, TimeChannelID},
+    speed_curve::SpeedCurve,
     error::EAnimationError,
-    frame_curve_manager::FrameCurveInfo,
-    loop_mode::ELoopMode,
-    runtime_info::RuntimeInfoMap,
+    frame_curve_manager::{FrameCurveInfo, FrameCurveInfoID, SharedFrameCurveInfoManager, SharedFrameCurvePool},
+    loop_mode::{ELoopMode, IterationRange, IterationCount, TLoopMode},
+    runtime_info::{RuntimeInfo, RuntimeInfoMap},
     target_animation::TargetAnimation,
     target_modifier::{
         IDAnimatableAttr,
         TAnimatableTargetModifier,
-    }, base::{EFillMode, TimeMS},
+    }, base::{EFillMode, TimeMS, ETimeStepMode, ELargeDeltaPolicy, IterationDelay},
 };
 
 /// 类型动画上下文 - 每种数据类型的动画实现一个
+/// * 同一曲线资产 (以 `D::as_ref` 得到的曲线地址作为资产标识) 只保存一份, 由引用计数管理
+/// * 曲线在最后一个引用它的 AnimationInfo 被移除时才释放
+/// * 通过 new_in_store 创建时, 曲线描述信息和曲线数据 保存在外部共享的
+///   TFrameCurveInfoManager 和 TFrameCurvePool 中, 引用计数也记录在共享存储中,
+///   共享的引用计数归零时 由最后释放的上下文移除曲线
 pub struct TypeAnimationContext<F: FrameDataValue, D: AsRef<FrameCurve<F>>> {
     ty: KeyFrameDataType,
+    /// 外部共享的曲线存储
+    store: Option<FrameCurveStore<F, D>>,
+    /// 不使用外部共享曲线存储时 的曲线数据
     curves: Vec<Option<D>>,
+    /// 各曲线被 AnimationInfo 引用的计数
+    curve_refs: Vec<usize>,
+    /// 曲线资产标识 到 曲线ID 的映射
+    curve_keys: XHashMap<usize, FrameCurveInfoID>,
     id_pool: Vec<usize>,
     pd: PhantomData<F>,
 }
 
+/// 外部共享的曲线存储
+struct FrameCurveStore<F: FrameDataValue, D: AsRef<FrameCurve<F>>> {
+    curve_infos: SharedFrameCurveInfoManager,
+    curve_pool: SharedFrameCurvePool<F, D>,
+}
+
 impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
     pub fn new<T: Clone  + PartialEq + Eq + Hash>(
         ty: usize,
@@ -43,15 +67,109 @@ impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
         runtime_info_map.add_type(ty);
         Self {
             ty,
+            store: None,
             curves: vec![],
+            curve_refs: vec![],
+            curve_keys: XHashMap::default(),
             id_pool: vec![],
             pd: PhantomData::default()
         }
     }
+    /// 创建 使用外部共享曲线存储 的类型动画上下文
+    /// * create_animation / remove_one / replace_curve / anime 均使用共享存储
+    /// * 也可通过 insert_curve_to_store 预先添加曲线, 再通过 create_animation_from_store 创建动画
+    pub fn new_in_store<T: Clone + PartialEq + Eq + Hash>(
+        ty: usize,
+        runtime_info_map: &mut RuntimeInfoMap<T>,
+        curve_infos: SharedFrameCurveInfoManager,
+        curve_pool: SharedFrameCurvePool<F, D>,
+    ) -> Result<Self, EAnimationError> {
+        let result = curve_infos.lock().unwrap().add_type(ty);
+        match result {
+            Ok(_) => {
+                let mut result = Self::new(ty, runtime_info_map);
+                result.store = Some(FrameCurveStore { curve_infos, curve_pool });
+                Ok(result)
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// 是否使用外部共享的曲线存储
+    pub fn in_store(&self) -> bool {
+        self.store.is_some()
+    }
+    /// 上下文自身保存的曲线 - 使用外部共享曲线存储时为空
     pub fn curves(&self) -> &Vec<Option<D>> {
         &self.curves
     }
+    /// 曲线的资产标识 - 共享同一份曲线数据的 D 得到相同的标识
+    /// * 仅对 地址稳定的共享句柄 有效, 参见 create_animation
+    fn curve_asset_key(curve: &D) -> usize {
+        curve.as_ref() as *const FrameCurve<F> as usize
+    }
+    /// 查询 曲线资产 已使用的 曲线ID
+    pub fn curve_id(&self, curve: &D) -> Option<FrameCurveInfoID> {
+        let key = Self::curve_asset_key(curve);
+        match self.curve_keys.get(&key) {
+            Some(index) if self.is_curve_key(key, *index) => Some(*index),
+            _ => None,
+        }
+    }
+    /// 资产映射是否有效 - 映射可能已过期, 需确认该位置仍是同一资产
+    /// * 使用外部共享曲线存储时 曲线可能已被其他上下文释放
+    fn is_curve_key(&self, key: usize, index: FrameCurveInfoID) -> bool {
+        match &self.store {
+            Some(store) => match store.curve_pool.lock().unwrap().get(index) {
+                Ok(item) => Self::curve_asset_key(item) == key,
+                Err(_) => false,
+            },
+            None => match self.curves.get(index) {
+                Some(Some(item)) => Self::curve_asset_key(item) == key,
+                _ => false,
+            },
+        }
+    }
+    /// 查询 曲线 数据
+    /// * 使用外部共享曲线存储时 曲线数据在共享的曲线数据池中, 无法返回引用, 始终返回 None; 使用 with_curve 查询
+    pub fn curve(&self, curve_id: FrameCurveInfoID) -> Option<&D> {
+        match self.curves.get(curve_id) {
+            Some(curve) => curve.as_ref(),
+            None => None,
+        }
+    }
+    /// 使用 曲线 数据 - 曲线不存在时 返回 None
+    /// * 使用外部共享曲线存储时 从共享的曲线数据池读取
+    pub fn with_curve<R>(&self, curve_id: FrameCurveInfoID, call: impl FnOnce(&D) -> R) -> Option<R> {
+        match &self.store {
+            Some(store) => match store.curve_pool.lock().unwrap().get(curve_id) {
+                Ok(curve) => Some(call(curve)),
+                Err(_) => None,
+            },
+            None => self.curve(curve_id).map(call),
+        }
+    }
+    /// 查询 曲线 被多少个 AnimationInfo 使用
+    pub fn curve_ref_count(&self, curve_id: FrameCurveInfoID) -> usize {
+        match &self.store {
+            Some(store) => store.curve_infos.lock().unwrap().ref_count(self.ty, curve_id),
+            None => match self.curve_refs.get(curve_id) {
+                Some(count) => *count,
+                None => 0,
+            },
+        }
+    }
+    /// 当前保存的 曲线 数目
+    /// * 使用外部共享曲线存储时 为本上下文通过 create_animation 添加到存储中 且仍未释放的曲线数目
+    pub fn curve_count(&self) -> usize {
+        match &self.store {
+            Some(_) => self.curve_keys.iter().filter(|(key, index)| self.is_curve_key(**key, **index)).count(),
+            None => self.curves.len() - self.id_pool.len(),
+        }
+    }
     /// 添加 动画曲线数据
+    /// * 曲线资产已存在时 复用已有曲线, 只增加引用计数
+    /// * 以 `D::as_ref` 得到的曲线地址作为资产标识, D 必须是地址稳定的共享句柄 (如 `Arc<FrameCurve<F>>`);
+    ///   内联持有 FrameCurve 的 D 移动后地址改变, 且释放后的地址可能被无关曲线复用, 不可用于复用判断
     pub fn create_animation(
         &mut self,
         attr: IDAnimatableAttr,
@@ -59,6 +177,37 @@ impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
     ) -> AnimationInfo {
         let curve_info = FrameCurveInfo::from(curve.as_ref());
 
+        if let Some(store) = &self.store {
+            let curve_id = match self.curve_id(&curve) {
+                Some(curve_id) => curve_id,
+                None => {
+                    let key = Self::curve_asset_key(&curve);
+                    // 数据类型已在 new_in_store 中添加, insert 不会失败
+                    let curve_id = store.curve_infos.lock().unwrap().insert(self.ty, curve_info).unwrap();
+                    store.curve_pool.lock().unwrap().insert(curve_id, curve);
+                    self.curve_keys.insert(key, curve_id);
+                    curve_id
+                }
+            };
+            let _ = store.curve_infos.lock().unwrap().add_ref(self.ty, curve_id);
+            return AnimationInfo {
+                attr,
+                ty: self.ty,
+                curve_info,
+                curve_id,
+            };
+        }
+
+        if let Some(index) = self.curve_id(&curve) {
+            self.curve_refs[index] += 1;
+            return AnimationInfo {
+                attr,
+                ty: self.ty,
+                curve_info,
+                curve_id: index,
+            };
+        }
+
         // if let Some(index) = self.id_pool.pop() {
         //     let result = AnimationInfo {
         //         attr,
@@ -88,40 +237,169 @@ impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
         
         if index == self.curves.len() {
             self.curves.push(Some(curve));
+            self.curve_refs.push(1);
         } else {
             self.curves[index] = Some(curve);
+            self.curve_refs[index] = 1;
         }
+        let key = Self::curve_asset_key(self.curves[index].as_ref().unwrap());
+        self.curve_keys.insert(key, index);
+
         result
 
     }
+    /// 替换 曲线 数据 - 所有使用该曲线的动画 在下一帧使用新的曲线
+    /// * 返回新曲线的描述信息, 需同步到使用该曲线的动画组, 参见 AnimationContextAmount.replace_curve
+    pub fn replace_curve(
+        &mut self,
+        curve_id: FrameCurveInfoID,
+        curve: D,
+    ) -> Result<FrameCurveInfo, EAnimationError> {
+        let curve_info = FrameCurveInfo::from(curve.as_ref());
+        let key = Self::curve_asset_key(&curve);
+        // 新曲线资产 已被其他曲线ID 使用时 不覆盖其映射
+        let shared = self.curve_id(&curve).is_some();
+
+        if let Some(store) = &self.store {
+            let result = store.curve_infos.lock().unwrap().replace(self.ty, curve_id, curve_info);
+            if let Err(e) = result {
+                return Err(e);
+            }
+            let mut curve_pool = store.curve_pool.lock().unwrap();
+            let old_key = curve_pool.get(curve_id).ok().map(|old| Self::curve_asset_key(old));
+            curve_pool.insert(curve_id, curve);
+            // 只维护 本上下文添加的曲线 的资产映射
+            if let Some(old_key) = old_key {
+                if self.curve_keys.get(&old_key) == Some(&curve_id) {
+                    self.curve_keys.remove(&old_key);
+                    if !shared {
+                        self.curve_keys.insert(key, curve_id);
+                    }
+                }
+            }
+            return Ok(curve_info);
+        }
+
+        let old_key = match self.curves.get(curve_id) {
+            Some(Some(old)) => Self::curve_asset_key(old),
+            _ => return Err(EAnimationError::FrameCurveNotFound),
+        };
+
+        self.curves[curve_id] = Some(curve);
+        if self.curve_keys.get(&old_key) == Some(&curve_id) {
+            self.curve_keys.remove(&old_key);
+        }
+        if !shared {
+            self.curve_keys.insert(key, curve_id);
+        }
+
+        Ok(curve_info)
+    }
+    /// 按 曲线资产 替换 曲线 数据
+    pub fn replace_curve_by_asset(
+        &mut self,
+        old: &D,
+        curve: D,
+    ) -> Result<(FrameCurveInfoID, FrameCurveInfo), EAnimationError> {
+        match self.curve_id(old) {
+            Some(curve_id) => match self.replace_curve(curve_id, curve) {
+                Ok(curve_info) => Ok((curve_id, curve_info)),
+                Err(e) => Err(e),
+            },
+            None => Err(EAnimationError::FrameCurveNotFound),
+        }
+    }
+    /// 添加 曲线 到外部共享的曲线存储
+    /// * 返回的曲线ID 可用于多个动画
+    /// * 调用方持有曲线的一个引用, 通过 remove_curve_from_store 释放
+    pub fn insert_curve_to_store(
+        &self,
+        curve: D,
+    ) -> Result<FrameCurveInfoID, EAnimationError> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Err(EAnimationError::FrameCurveTypeNotFound),
+        };
+        let result = store.curve_infos.lock().unwrap().insert(self.ty, FrameCurveInfo::from(curve.as_ref()));
+        match result {
+            Ok(curve_id) => {
+                store.curve_pool.lock().unwrap().insert(curve_id, curve);
+                let _ = store.curve_infos.lock().unwrap().add_ref(self.ty, curve_id);
+                Ok(curve_id)
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// 释放 insert_curve_to_store 时调用方持有的引用, 并从外部共享的曲线存储 移除曲线
+    /// * 曲线仍被动画使用时 返回 FrameCurveInUse, 调用方仍持有引用
+    pub fn remove_curve_from_store(
+        &self,
+        curve_id: FrameCurveInfoID,
+    ) -> Result<(), EAnimationError> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Err(EAnimationError::FrameCurveTypeNotFound),
+        };
+        let mut curve_infos = store.curve_infos.lock().unwrap();
+        if curve_infos.ref_count(self.ty, curve_id) > 1 {
+            return Err(EAnimationError::FrameCurveInUse);
+        }
+        let _ = curve_infos.release(self.ty, curve_id);
+        match curve_infos.remove(self.ty, curve_id) {
+            Ok(_) => store.curve_pool.lock().unwrap().remove(curve_id),
+            Err(e) => Err(e),
+        }
+    }
+    /// 使用外部共享曲线存储中的曲线 创建动画
+    /// * 增加曲线的引用计数, 动画移除时 (remove_one) 减少
+    pub fn create_animation_from_store(
+        &self,
+        attr: IDAnimatableAttr,
+        curve_id: FrameCurveInfoID,
+    ) -> Result<AnimationInfo, EAnimationError> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Err(EAnimationError::FrameCurveTypeNotFound),
+        };
+        let mut curve_infos = store.curve_infos.lock().unwrap();
+        match curve_infos.get(self.ty, curve_id) {
+            Ok(curve_info) => match curve_infos.add_ref(self.ty, curve_id) {
+                Ok(_) => Ok(AnimationInfo {
+                    attr,
+                    ty: self.ty,
+                    curve_info,
+                    curve_id,
+                }),
+                Err(e) => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
     /// 使用曲线计算结果 计算属性值
+    /// * 使用外部共享曲线存储时 从共享的曲线数据池读取曲线
     pub fn anime<T: Clone + PartialEq + Eq + Hash, R: TypeAnimationResultPool<F, T>>(
         &self,
         runtime_infos: &RuntimeInfoMap<T>,
         result_pool: &mut R,
     ) -> Result<(), Vec<EAnimationError>> {
-        let mut errs = vec![];
-        let runtime_infos = runtime_infos.get_type_list(self.ty).unwrap();
+        let runtime_infos = match runtime_infos.get_type_list(self.ty) {
+            Some(runtime_infos) => runtime_infos,
+            None => return Err(vec![EAnimationError::RuntimeInfoMapNotFindType]),
+        };
         // log::trace!("anime, runtime_infos len: {}", runtime_infos.len());
         // println!("anime, runtime_infos len: {}", runtime_infos.len());
 
-        for (target, info) in runtime_infos {
-            info.iter().for_each(|info| {
-                if let Some(Some(curve)) = self.curves.get(info.curve_id) {
-                    // println!(">>>>>>>>>>>>>>>>>{}", info.amount_in_second);
-                    let value = curve.as_ref().interple(info.amount_in_second, &info.amount_calc);
-                    let result = AnimeResult {
-                        value,
-                        attr: info.attr,
-                        weight: info.group_weight,
-                    };
-                    match result_pool.record_result(target.clone(), info.attr, result) {
-                        Ok(_) => {}
-                        Err(e) => errs.push(e),
-                    }
-                }
-            });
-        }
+        let errs = match &self.store {
+            Some(store) => {
+                let curve_pool = store.curve_pool.lock().unwrap();
+                _anime_curves(runtime_infos, |curve_id| curve_pool.get(curve_id).ok(), result_pool)
+            },
+            None => _anime_curves(runtime_infos, |curve_id| match self.curves.get(curve_id) {
+                Some(Some(curve)) => Some(curve),
+                _ => None,
+            }, result_pool),
+        };
 
         if errs.len() > 0 {
             // println!("Error Number {}", errs.len());
@@ -138,18 +416,14 @@ impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
         result_pool: &mut R,
     ) {
         let runtime_infos = runtime_infos.get_type_list(self.ty).unwrap();
-        for (target, info) in runtime_infos {
-            info.iter().for_each(|info| {
-                let curve = self.curves.get(info.curve_id).unwrap().as_ref().unwrap();
-                // println!(">>>>>>>>>>>>>>>>>{}", info.amount_in_second);
-                let value = curve.as_ref().interple(info.amount_in_second, &info.amount_calc);
-                let result = AnimeResult {
-                    value,
-                    attr: info.attr,
-                    weight: info.group_weight,
-                };
-                let _ = result_pool.record_result(target.clone(), info.attr, result);
-            });
+        match &self.store {
+            Some(store) => {
+                let curve_pool = store.curve_pool.lock().unwrap();
+                _anime_curves_uncheck(runtime_infos, |curve_id| curve_pool.get(curve_id).unwrap(), result_pool);
+            },
+            None => {
+                _anime_curves_uncheck(runtime_infos, |curve_id| self.curves.get(curve_id).unwrap().as_ref().unwrap(), result_pool);
+            },
         }
     }
 
@@ -173,13 +447,45 @@ impl<F: FrameDataValue, D: AsRef<FrameCurve<F>>> TypeAnimationContext<F, D> {
 
     /// 移除动画对应的曲线信息
     /// * animations 为 AnimationContextAmount.del_animation_group 的返回值
+    /// * 曲线引用计数归零时 才释放曲线
+    /// * 使用外部共享曲线存储时 减少存储中的引用计数, 归零时从存储中移除曲线 - 与曲线由哪个上下文添加无关
     pub fn remove_one(
         &mut self,
         animation: &AnimationInfo,
     ) {
-        if animation.ty == self.ty {
-            self.curves[animation.curve_id] = None;
-            self.id_pool.push(animation.curve_id);
+        if animation.ty != self.ty {
+            return;
+        }
+        let index = animation.curve_id;
+        if let Some(store) = &self.store {
+            let mut curve_infos = store.curve_infos.lock().unwrap();
+            if let Ok(0) = curve_infos.release(self.ty, index) {
+                let mut curve_pool = store.curve_pool.lock().unwrap();
+                if let Ok(curve) = curve_pool.get(index) {
+                    let key = Self::curve_asset_key(curve);
+                    if self.curve_keys.get(&key) == Some(&index) {
+                        self.curve_keys.remove(&key);
+                    }
+                }
+                let _ = curve_infos.remove(self.ty, index);
+                let _ = curve_pool.remove(index);
+            }
+            return;
+        }
+        match self.curve_refs.get_mut(index) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                if *count == 0 {
+                    if let Some(curve) = self.curves[index].take() {
+                        let key = Self::curve_asset_key(&curve);
+                        if self.curve_keys.get(&key) == Some(&index) {
+                            self.curve_keys.remove(&key);
+                        }
+                    }
+                    self.id_pool.push(index);
+                }
+            },
+            _ => {},
         }
     }
 }
@@ -201,8 +507,27 @@ pub struct AnimationContextAmount<T: Clone + PartialEq + Eq + Hash, M: Animation
     // pub group_infos: Vec<AnimationGroupRuntimeInfo>,
     pub group_infos: SecondaryMap<DefaultKey, AnimationGroupRuntimeInfo>,
     pub time_scale: f32,
-    pub group_events: Vec<(DefaultKey, EAnimationEvent, u32)>,
+    /// 新建动画组 使用的推进时间方式
+    time_step: ETimeStepMode,
+    /// 大间隔时间的处理方式 - 动画组未单独设置时使用
+    large_delta: ELargeDeltaPolicy,
+    /// 绝对时钟驱动时 上次计算的 (输入时刻, 动画时钟) - ms
+    clock: Option<(TimeMS, TimeMS)>,
+    /// 时间通道
+    time_channels: TimeChannels,
+    /// 本次计算产生的动画组事件 - 通过 events drain_events 拉取
+    events: Vec<AnimationEvent>,
+    /// 两次计算之间 由 pause stop seek 等操作产生的事件, 在下次计算时并入 events
+    pending_events: Vec<AnimationEvent>,
+    /// 每次计算后 将事件转发到的通道
+    event_sender: Option<Sender<AnimationEvent>>,
+    /// 等待动画状态的 Future 记录
+    waiters: AnimationWaiters,
+    /// 事件派发器
+    dispatcher: AnimationEventDispatcher,
     pub removed_animations: Vec<AnimationInfo>,
+    /// 动画目标 到 作用于它的动画组 的反向索引
+    target_groups: XHashMap<T, Vec<AnimationGroupID>>,
     mark: PhantomData<T>,
 }
 
@@ -212,8 +537,17 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
             group_mgr,
             group_infos: SecondaryMap::default(),
             time_scale: 1.0,
-            group_events: vec![],
+            time_step: ETimeStepMode::default(),
+            large_delta: ELargeDeltaPolicy::default(),
+            clock: None,
+            time_channels: TimeChannels::default(),
+            events: vec![],
+            pending_events: vec![],
+            event_sender: None,
+            waiters: AnimationWaiters::default(),
+            dispatcher: AnimationEventDispatcher::default(),
             removed_animations: vec![],
+            target_groups: XHashMap::default(),
             mark: PhantomData,
         }
     }
@@ -227,19 +561,205 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
             group.debug = flag;
         }
     }
+    /// 设置推进时间的方式 - 作用于所有动画组 及之后创建的动画组
+    pub fn time_step_mode(
+        &mut self,
+        mode: ETimeStepMode,
+    ) {
+        self.time_step = mode;
+        for (i, _) in self.group_infos.iter() {
+            if let Some(group) = self.group_mgr.get_mut(i) {
+                group.time_step = mode;
+            }
+        }
+    }
+    /// 设置指定动画组 推进时间的方式
+    pub fn set_group_time_step_mode(&mut self, id: AnimationGroupID, mode: ETimeStepMode) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.time_step = mode;
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置指定动画组的结束延时 - 播放完成后 保持最终状态的时间, 之后才触发结束事件
+    /// * 结束延时中 按 EFillMode::FORWARDS 决定是否保持最终状态
+    /// * 需要在启动动画前修改
+    pub fn set_group_end_delay(&mut self, id: AnimationGroupID, end_delay_ms: TimeMS) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.end_delay_ms = end_delay_ms.max(0.);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置指定动画组的循环范围 - 对应 CSS 的 animation-iteration-start 和 animation-iteration-count
+    /// * `start` 起始位置 - 以循环次数计, 如 0.3 表示从第一次循环的 30% 处开始
+    /// * `count` 循环次数 - 可为小数, None 时使用循环模式的次数
+    /// * 需要在启动动画前修改
+    pub fn set_group_iteration_range(&mut self, id: AnimationGroupID, start: IterationCount, count: Option<IterationCount>) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.iteration = IterationRange::new(start, count);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置指定动画组的自定义循环模式 - None 时使用启动时指定的 ELoopMode
+    /// * 播放次数 仍由 ELoopMode 或 循环范围 指定
+    pub fn set_group_loop_calc(&mut self, id: AnimationGroupID, loop_calc: Option<Arc<dyn TLoopMode>>) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.set_loop_calc(loop_calc);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置指定动画组 循环之间的停顿 - None 时不停顿
+    pub fn set_group_iteration_delay(&mut self, id: AnimationGroupID, setting: Option<IterationDelay>) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.set_iteration_delay(setting);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置大间隔时间的处理方式 - 作用于未单独设置的动画组
+    pub fn set_large_delta_policy(&mut self, policy: ELargeDeltaPolicy) {
+        self.large_delta = policy;
+    }
+    /// 单独设置动画组 大间隔时间的处理方式 - None 时使用 context 的设置
+    pub fn set_group_large_delta_policy(&mut self, id: AnimationGroupID, policy: Option<ELargeDeltaPolicy>) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.large_delta = policy;
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 设置动画组的播放速度曲线 - None 时按恒定 speed 播放
+    pub fn set_group_speed_curve(&mut self, id: AnimationGroupID, curve: Option<SpeedCurve>) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.set_speed_curve(curve);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 动画组距离播放结束的剩余时间 - 参见 AnimationGroup::remaining_time_ms
+    /// * 剩余时间 不计 time_scale 和 时间通道的缩放
+    pub fn remaining_time_ms(&self, id: AnimationGroupID) -> Result<Option<TimeMS>, EAnimationError> {
+        match self.group_mgr.get(id) {
+            Some(group) => Ok(group.remaining_time_ms()),
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 创建时间通道
+    /// * `parent` 父通道 - None 时直接受 time_scale 控制
+    pub fn create_time_channel(&mut self, name: &str, parent: Option<TimeChannelID>) -> Result<TimeChannelID, EAnimationError> {
+        self.time_channels.create(name, parent)
+    }
+    /// 移除时间通道 - 通道内的动画组 和 子通道 转到被移除通道的父通道
+    pub fn remove_time_channel(&mut self, id: TimeChannelID) -> Result<(), EAnimationError> {
+        match self.time_channels.remove(id) {
+            Ok(parent) => {
+                for (i, _) in self.group_infos.iter() {
+                    if let Some(group) = self.group_mgr.get_mut(i) {
+                        if group.time_channel == Some(id) {
+                            group.time_channel = parent;
+                        }
+                    }
+                }
+                Ok(())
+            },
+            Err(e) => Err(e),
+        }
+    }
+    /// 以名称查找时间通道
+    pub fn time_channel(&self, name: &str) -> Option<TimeChannelID> {
+        self.time_channels.id(name)
+    }
+    pub fn time_channels(&self) -> &TimeChannels {
+        &self.time_channels
+    }
+    /// 设置时间通道的缩放
+    pub fn set_time_channel_scale(&mut self, id: TimeChannelID, scale: f32) -> Result<(), EAnimationError> {
+        match self.time_channels.get_mut(id) {
+            Some(channel) => {
+                channel.scale = scale;
+                Ok(())
+            },
+            None => Err(EAnimationError::TimeChannelNotFound),
+        }
+    }
+    /// 暂停 或 恢复时间通道 - 作用于通道及其子通道内的所有动画组
+    pub fn set_time_channel_paused(&mut self, id: TimeChannelID, paused: bool) -> Result<(), EAnimationError> {
+        match self.time_channels.get_mut(id) {
+            Some(channel) => {
+                channel.paused = paused;
+                Ok(())
+            },
+            None => Err(EAnimationError::TimeChannelNotFound),
+        }
+    }
+    /// 设置动画组所属的时间通道 - None 时只受 time_scale 控制
+    /// * 绝对时钟驱动时 动画组记录的时刻 转换到新通道的时钟, 保持当前进度
+    pub fn set_group_time_channel(&mut self, id: AnimationGroupID, channel: Option<TimeChannelID>) -> Result<(), EAnimationError> {
+        if let Some(channel) = channel {
+            if self.time_channels.get(channel).is_none() {
+                return Err(EAnimationError::TimeChannelNotFound);
+            }
+        }
+        let clock_ms = self.clock_ms();
+        let time_channels = &self.time_channels;
+        let channel_clock = |channel: Option<TimeChannelID>| match channel.and_then(|channel| time_channels.clock(channel)) {
+            Some((clock_ms, _)) => Some(clock_ms),
+            None => clock_ms,
+        };
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                if let (Some(from), Some(to)) = (channel_clock(group.time_channel), channel_clock(channel)) {
+                    group.rebase_clock(to - from);
+                }
+                group.time_channel = channel;
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
     /// 创建动画组
     pub fn create_animation_group(&mut self) -> AnimationGroupID {
         let id = self.group_mgr.create();
+        if let Some(group) = self.group_mgr.get_mut(id) {
+            group.time_step = self.time_step;
+        }
         self.group_infos.insert(
             id,
             AnimationGroupRuntimeInfo {
                 last_amount_in_second: 0.,
                 amount_in_second: 0.,
+                last_looped_count: 0,
                 looped_count: 0,
+                loop_crossed: 0,
                 is_playing: false,
                 loop_event: false,
                 start_event: false,
                 end_event: false,
+                delay_end_event: false,
+                suppress_event: false,
+                last_running_time_ms: 0.,
+                running_time_ms: 0.,
+                once_time_ms: 0.,
+                loop_mode: ELoopMode::Not,
+                positive_pass: true,
+                iteration: IterationRange::default(),
             },
         );
         // if id >= self.group_infos.len() {
@@ -266,51 +786,54 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         }
     }
 	pub fn remove_animation_group<AM: AnimationContextMgr>(&mut self, id: AnimationGroupID, mgr: &mut AM) {
-		match self.group_infos.get_mut(id) {
-            Some(group_info) => {
-                group_info.is_playing = false;
-                group_info.amount_in_second = 0.;
-                group_info.last_amount_in_second = 0.;
-                group_info.looped_count = 0;
-                group_info.start_event = false;
-                group_info.end_event = false;
-                group_info.loop_event = false;
-                self.group_mgr.del(id).drain(..).for_each(|item| {
-					mgr.remove_curve(&item);
-                });
-            }
-            None => {
-            }
-        }
+		self.finish_removed_group(id).drain(..).for_each(|item| {
+			mgr.remove_curve(&item);
+		});
 	}
     /// 删除动画组 - 自动记录移除的 AnimationInfo,
     /// 后续 在合适时机 调用 apply_removed_animations 和 clear_removed_animations
     pub fn del_animation_group(&mut self, id: AnimationGroupID) {
+        let mut animations = self.finish_removed_group(id);
+        self.removed_animations.append(&mut animations);
+    }
+    /// 结束并删除动画组 - remove_animation_group 与 del_animation_group 共用
+    /// * 清理索引和等待, 发出 区间离开、Cancel、Remove 事件, 重置运行信息
+    /// * 返回被移除的 AnimationInfo
+    fn finish_removed_group(&mut self, id: AnimationGroupID) -> Vec<AnimationInfo> {
+        self.forget_group_targets(id);
+        self.dispatcher.remove_group(id);
+        self.waiters.remove_group(id);
         match self.group_infos.get_mut(id) {
             Some(group_info) => {
+                let position = group_info.position();
+                if let Some(group) = self.group_mgr.get_mut(id) {
+                    group.exit_ranges().drain(..).for_each(|(ty, data)| {
+                        self.pending_events.extend(AnimationEvent::new(id, ty, data as u32));
+                    });
+                    if group.is_playing() {
+                        self.pending_events.push(AnimationEvent::Cancel(id, EAnimationEndReason::Removed, position));
+                    }
+                    self.pending_events.push(AnimationEvent::Remove(id, position));
+                }
                 group_info.is_playing = false;
                 group_info.amount_in_second = 0.;
                 group_info.last_amount_in_second = 0.;
                 group_info.looped_count = 0;
+                group_info.last_running_time_ms = 0.;
+                group_info.running_time_ms = 0.;
                 group_info.start_event = false;
                 group_info.end_event = false;
                 group_info.loop_event = false;
-                self.group_mgr.del(id).drain(..).for_each(|item| {
-                    self.removed_animations.push(item);
-                });
-            }
-            None => {
+                self.group_mgr.del(id)
             }
+            None => vec![],
         }
     }
     /// 在各动画数据类型的上下文 应用 动画的移除记录
     pub fn apply_removed_animations<F: FrameDataValue, D: AsRef<FrameCurve<F>>>(&self, typectx: &mut TypeAnimationContext<F, D>) {
         
         self.removed_animations.iter().for_each(|anime| {
-            if anime.ty == typectx.ty {
-                typectx.curves[anime.curve_id] = None;
-                typectx.id_pool.push(anime.curve_id);
-            }
+            typectx.remove_one(anime);
         });
     }
     /// 清空 已移除动画的记录
@@ -347,7 +870,9 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         match self.group_mgr.get_mut(group_id) {
             Some(group) => {
                 let animation = type_ctx.create_animation(0, curve);
-                group.add_target_animation(TargetAnimation { target, animation })
+                let result = group.add_target_animation(TargetAnimation::new(target.clone(), animation));
+                self.record_target_group(target, group_id);
+                result
             },
             None => Err(EAnimationError::AnimationGroupNotFound),
         }
@@ -361,11 +886,222 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
     ) -> Result<(), EAnimationError> {
         match self.group_mgr.get_mut(group_id) {
             Some(group) => {
-                group.add_target_animation(TargetAnimation { target, animation })
+                let result = group.add_target_animation(TargetAnimation::new(target.clone(), animation));
+                self.record_target_group(target, group_id);
+                result
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 记录 动画目标 与 动画组 的关联
+    fn record_target_group(&mut self, target: T, group_id: AnimationGroupID) {
+        match self.target_groups.get_mut(&target) {
+            Some(list) => {
+                if !list.contains(&group_id) {
+                    list.push(group_id);
+                }
+            },
+            None => {
+                self.target_groups.insert(target, vec![group_id]);
+            },
+        }
+    }
+    /// 移除 动画组 在反向索引中的记录
+    fn forget_group_targets(&mut self, id: AnimationGroupID) {
+        if let Some(group) = self.group_mgr.get(id) {
+            for anime in group.animations().iter() {
+                let empty = match self.target_groups.get_mut(&anime.target) {
+                    Some(list) => {
+                        list.retain(|item| *item != id);
+                        list.len() == 0
+                    },
+                    None => false,
+                };
+                if empty {
+                    self.target_groups.remove(&anime.target);
+                }
+            }
+        }
+    }
+    /// 查询 作用于目标的动画组
+    pub fn target_groups(&self, target: &T) -> Option<&Vec<AnimationGroupID>> {
+        self.target_groups.get(target)
+    }
+    /// 移除 目标 的所有动画
+    /// * 从所有动画组中移除作用于该目标的 Target动画, 记录到 removed_animations
+    /// * 移除后没有动画的动画组 被删除
+    /// * 返回被删除的动画组
+    /// * 动画结果池中 目标的记录 需调用 TypeAnimationResultPool.remove_target 清理,
+    ///   或使用 TypeAnimationRegistry.remove_target
+    pub fn remove_target(&mut self, target: &T) -> Vec<AnimationGroupID> {
+        let mut result = vec![];
+        let groups = match self.target_groups.remove(target) {
+            Some(groups) => groups,
+            None => return result,
+        };
+
+        for id in groups {
+            if let (Some(group), Some(group_info)) = (self.group_mgr.get_mut(id), self.group_infos.get_mut(id)) {
+                group.remove_target(target, group_info).drain(..).for_each(|item| {
+                    self.removed_animations.push(item);
+                });
+                if group.animations().len() == 0 {
+                    result.push(id);
+                }
+            }
+        }
+
+        result.iter().for_each(|id| {
+            self.del_animation_group(*id);
+        });
+
+        result
+    }
+    /// 替换 曲线 数据, 并更新使用该曲线的动画组
+    /// * 动画组的总帧数 重新计算, 正在运行的动画组 保持归一化的进度
+    /// * 返回 受影响的动画组
+    pub fn replace_curve<F: FrameDataValue, D: AsRef<FrameCurve<F>>>(
+        &mut self,
+        type_ctx: &mut TypeAnimationContext<F, D>,
+        curve_id: FrameCurveInfoID,
+        curve: D,
+    ) -> Result<Vec<AnimationGroupID>, EAnimationError> {
+        match type_ctx.replace_curve(curve_id, curve) {
+            Ok(curve_info) => Ok(self.update_curve_info(type_ctx.ty(), curve_id, curve_info)),
+            Err(e) => Err(e),
+        }
+    }
+    /// 更新 使用指定曲线的动画 的曲线描述信息
+    /// * 动画组的运行信息 一起按比例调整
+    /// * 返回 受影响的动画组
+    pub fn update_curve_info(
+        &mut self,
+        ty: KeyFrameDataType,
+        curve_id: FrameCurveInfoID,
+        curve_info: FrameCurveInfo,
+    ) -> Vec<AnimationGroupID> {
+        let mut result = vec![];
+        for (id, group_info) in self.group_infos.iter_mut() {
+            if let Some(group) = self.group_mgr.get_mut(id) {
+                if group.update_curve_info(ty, curve_id, curve_info, group_info) {
+                    result.push(id);
+                }
+            }
+        }
+        result
+    }
+    /// 设置动画组 一次计算跨过多次播放时 帧事件的补发方式
+    pub fn set_frame_event_catch_up(&mut self, id: AnimationGroupID, catch_up: EFrameEventCatchUp) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.frame_event_catch_up = catch_up;
+                Ok(())
             },
             None => Err(EAnimationError::AnimationGroupNotFound),
         }
     }
+    /// 为动画组添加帧事件 - 在动画组的 各次播放 中自动产生 FrameEvent 事件
+    /// * `frame` 事件所在帧, 与曲线帧位置一致
+    /// * `design_frame_per_second` 帧位置对应的设计帧率
+    pub fn add_frame_event(
+        &mut self,
+        id: AnimationGroupID,
+        frame: KeyFrameCurveValue,
+        design_frame_per_second: FramePerSecond,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => group.frame_events_mut().add_frame(frame, design_frame_per_second, data),
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 为动画组添加帧事件
+    /// * `time_ms` 事件在曲线时间轴上的位置 - 毫秒, 与播放速度无关
+    pub fn add_frame_event_ms(
+        &mut self,
+        id: AnimationGroupID,
+        time_ms: TimeMS,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.frame_events_mut().add_ms(time_ms, data);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 为动画组中 作用于目标指定属性的 Target动画 添加帧事件
+    /// * 事件随该 Target动画 一起移除
+    pub fn add_target_frame_event(
+        &mut self,
+        id: AnimationGroupID,
+        target: &T,
+        attr: IDAnimatableAttr,
+        frame: KeyFrameCurveValue,
+        design_frame_per_second: FramePerSecond,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => match group.target_frame_events_mut(target, attr) {
+                Some(events) => events.add_frame(frame, design_frame_per_second, data),
+                None => Err(EAnimationError::TargetAnimationNotFound),
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 为动画组添加区间事件 - 动画组时间进入和离开区间时 派发 RangeEnter RangeExit
+    /// * `start_frame` `end_frame` 区间的起止帧, 包含两端
+    /// * `design_frame_per_second` 帧位置对应的设计帧率
+    /// * 区间以 data 作为标识
+    pub fn add_range_event(
+        &mut self,
+        id: AnimationGroupID,
+        start_frame: KeyFrameCurveValue,
+        end_frame: KeyFrameCurveValue,
+        design_frame_per_second: FramePerSecond,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => group.frame_events_mut().add_range_frame(start_frame, end_frame, design_frame_per_second, data),
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 为动画组添加区间事件
+    /// * `start_ms` `end_ms` 区间在曲线时间轴上的位置 - 毫秒
+    pub fn add_range_event_ms(
+        &mut self,
+        id: AnimationGroupID,
+        start_ms: TimeMS,
+        end_ms: TimeMS,
+        data: FrameEventData,
+    ) -> Result<(), EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => {
+                group.frame_events_mut().add_range_ms(start_ms, end_ms, data);
+                Ok(())
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 动画组当前处于其中的区间事件
+    pub fn active_range_events(&self, id: AnimationGroupID) -> Option<&Vec<FrameEventData>> {
+        match self.group_mgr.get(id) {
+            Some(group) => Some(group.active_ranges()),
+            None => None,
+        }
+    }
+    /// 移除动画组上 携带指定数据的帧事件
+    pub fn remove_frame_event(
+        &mut self,
+        id: AnimationGroupID,
+        data: FrameEventData,
+    ) -> Result<bool, EAnimationError> {
+        match self.group_mgr.get_mut(id) {
+            Some(group) => Ok(group.frame_events_mut().remove(data)),
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
     /// 显式指定动画组总帧数
     /// * `total_frames` 动画组总帧数 指定 None 则自动使用内部动画曲线中最大帧数
     pub fn force_group_total_frames(
@@ -386,6 +1122,7 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
     /// * `seconds` 播放时长 - 秒
     /// * `loop_mode` 循环模式
     /// * `amount_calc` 播放进度变化控制
+    /// * `delay_time_ms` 延时 - 负值时 从播放途中开始
     pub fn start_complete(
         &mut self,
         id: AnimationGroupID,
@@ -393,8 +1130,17 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         loop_mode: ELoopMode,
         frame_per_second: FramePerSecond,
         amount_calc: AnimationAmountCalc,
-        delay_time_ms: KeyFrameCurveValue,
+        delay_time_ms: TimeMS,
         fillmode: EFillMode,
+    ) -> Result<(), EAnimationError> {
+        self.start_complete_with(id, seconds, AnimationStartParam::new(loop_mode, frame_per_second, amount_calc, delay_time_ms, fillmode))
+    }
+    /// 启动动画组 - 完整播放, 参数参见 AnimationStartParam
+    pub fn start_complete_with(
+        &mut self,
+        id: AnimationGroupID,
+        seconds: KeyFrameCurveValue,
+        param: AnimationStartParam,
     ) -> Result<(), EAnimationError> {
         match self.group_infos.get_mut(id) {
             Some(group_info) => match group_info.is_playing {
@@ -403,12 +1149,12 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
                     group_info.is_playing = true;
                     self.group_mgr.get_mut(id).unwrap().start_complete(
                         seconds.abs(),
-                        loop_mode,
-                        frame_per_second,
-                        amount_calc,
+                        param.loop_mode,
+                        param.frame_per_second,
+                        param.amount_calc,
                         group_info,
-                        delay_time_ms,
-                        fillmode,
+                        param.delay_time_ms,
+                        param.fillmode,
                     );
                     Ok(())
                 }
@@ -423,6 +1169,7 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
     /// * `to` 指定动画组的结束帧百分比位置 - 0~1
     /// * `frame_per_second` 指定动画组每秒运行多少帧 - 影响动画流畅度和计算性能
     /// * `amount_calc` 播放进度变化控制
+    /// * `delay_time_ms` 延时 - 负值时 从播放途中开始
     pub fn start_with_progress(
         &mut self,
         id: AnimationGroupID,
@@ -434,6 +1181,17 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         amount_calc: AnimationAmountCalc,
         delay_time_ms: TimeMS,
         fillmode: EFillMode,
+    ) -> Result<(), EAnimationError> {
+        self.start_with_progress_with(id, speed, from, to, AnimationStartParam::new(loop_mode, frame_per_second, amount_calc, delay_time_ms, fillmode))
+    }
+    /// 启动动画组 - 指定起止位置, 参数参见 AnimationStartParam
+    pub fn start_with_progress_with(
+        &mut self,
+        id: AnimationGroupID,
+        speed: KeyFrameCurveValue,
+        from: KeyFrameCurveValue,
+        to: KeyFrameCurveValue,
+        param: AnimationStartParam,
     ) -> Result<(), EAnimationError> {
         match self.group_infos.get_mut(id) {
             Some(group_info) => match group_info.is_playing {
@@ -442,14 +1200,14 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
                     group_info.is_playing = true;
                     self.group_mgr.get_mut(id).unwrap().start_with_progress(
                         speed,
-                        loop_mode,
+                        param.loop_mode,
                         from,
                         to,
-                        frame_per_second,
+                        param.frame_per_second,
                         group_info,
-                        amount_calc,
-                        delay_time_ms,
-                        fillmode,
+                        param.amount_calc,
+                        param.delay_time_ms,
+                        param.fillmode,
                     );
                     Ok(())
                 }
@@ -505,6 +1263,7 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
             Some(group_info) => match group_info.is_playing {
                 true => {
                     group_info.is_playing = false;
+                    self.pending_events.push(AnimationEvent::Pause(id, group_info.position()));
                     Ok(())
                 }
                 false => Err(EAnimationError::AnimationGroupNotPlaying),
@@ -513,21 +1272,51 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         }
     }
 
+    /// 跳转到动画组 当前这次播放 的指定进度
+    /// * `progress` 0~1 - 0 对应 from, 1 对应 to
+    /// * 跳转跨过的帧事件不触发, 区间进入离开事件在下次计算时派发
+    pub fn seek(&mut self, id: AnimationGroupID, progress: KeyFrameCurveValue) -> Result<(), EAnimationError> {
+        match self.group_infos.get_mut(id) {
+            Some(group_info) => match group_info.is_playing {
+                true => match self.group_mgr.get_mut(id) {
+                    Some(group) => {
+                        group.seek(progress, group_info).drain(..).for_each(|(ty, data)| {
+                            self.pending_events.extend(AnimationEvent::new(id, ty, data as u32));
+                        });
+                        Ok(())
+                    },
+                    None => Err(EAnimationError::AnimationGroupNotFound),
+                },
+                false => Err(EAnimationError::AnimationGroupNotPlaying),
+            },
+            None => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+
     /// 停止动画组
     pub fn stop(&mut self, id: AnimationGroupID) -> Result<(), EAnimationError> {
         match self.group_infos.get_mut(id) {
             Some(group_info) => match group_info.is_playing {
                 true => {
+                    let position = group_info.position();
                     group_info.is_playing = false;
                     group_info.amount_in_second = 0.;
                     group_info.last_amount_in_second = 0.;
                     group_info.looped_count = 0;
+                    group_info.last_running_time_ms = 0.;
+                    group_info.running_time_ms = 0.;
                     group_info.start_event = false;
                     group_info.end_event = false;
                     group_info.loop_event = false;
+                    self.waiters.record_end(id, EAnimationEndReason::Stopped);
                     match self.group_mgr.get_mut(id) {
                         Some(group) => {
                             group.stop();
+                            group.exit_ranges().drain(..).for_each(|(ty, data)| {
+                                self.pending_events.extend(AnimationEvent::new(id, ty, data as u32));
+                            });
+                            self.pending_events.push(AnimationEvent::Cancel(id, EAnimationEndReason::Stopped, position));
+                            self.pending_events.push(AnimationEvent::Stop(id, position));
                             Ok(())
                         }
                         None => Err(EAnimationError::AnimationGroupNotFound),
@@ -541,31 +1330,96 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
 
     /// 动画的曲线计算
     pub fn anime_curve_calc(&mut self, delta_ms: u64, runtime_infos: &mut RuntimeInfoMap<T>) {
-        self.group_events.clear();
+        self.anime_curve_calc_ms(delta_ms as TimeMS, runtime_infos);
+    }
+    /// 动画的曲线计算 - 间隔时间可以是小数毫秒
+    pub fn anime_curve_calc_ms(&mut self, delta_ms: TimeMS, runtime_infos: &mut RuntimeInfoMap<T>) {
+        let delta_ms = delta_ms * self.time_scale as TimeMS;
+        self.curve_calc(delta_ms, None, runtime_infos);
+    }
+    /// 动画的曲线计算 - 以 Duration 描述间隔时间
+    pub fn anime_curve_calc_duration(&mut self, delta: Duration, runtime_infos: &mut RuntimeInfoMap<T>) {
+        self.anime_curve_calc_ms(delta.as_secs_f64() * 1000., runtime_infos);
+    }
+    /// 以绝对时钟驱动的曲线计算 - 动画组记录启动时刻, 由时刻直接计算进度, 不累计误差
+    /// * `time_second` 当前时刻 - 秒, 可以是任意时刻, 包括回退到之前的时刻
+    /// * time_scale 作用于与上次计算的时刻差
+    /// * 不应与 anime_curve_calc 混用
+    pub fn anime_curve_calc_at(&mut self, time_second: f64, runtime_infos: &mut RuntimeInfoMap<T>) {
+        let time_ms = time_second * 1000.;
+        let (clock_ms, last_clock_ms) = match self.clock {
+            Some((last_time_ms, last_clock_ms)) => (last_clock_ms + (time_ms - last_time_ms) * self.time_scale as TimeMS, Some(last_clock_ms)),
+            None => (time_ms, None),
+        };
+        self.clock = Some((time_ms, clock_ms));
+        match last_clock_ms {
+            Some(last_clock_ms) => self.time_channels.advance_clock(clock_ms, clock_ms - last_clock_ms),
+            None => self.time_channels.reset_clock(clock_ms),
+        }
+        self.curve_calc(0., Some((clock_ms, last_clock_ms)), runtime_infos);
+    }
+    /// 绝对时钟驱动时 当前的动画时钟 - ms, 已应用 time_scale
+    pub fn clock_ms(&self) -> Option<TimeMS> {
+        self.clock.map(|(_, clock_ms)| clock_ms)
+    }
+    fn curve_calc(&mut self, delta_ms: TimeMS, clock: Option<(TimeMS, Option<TimeMS>)>, runtime_infos: &mut RuntimeInfoMap<T>) {
+        self.events.clear();
+        self.events.append(&mut self.pending_events);
 
-        let delta_ms = delta_ms as KeyFrameCurveValue * self.time_scale as KeyFrameCurveValue;
         let group_mgr = &mut self.group_mgr;
         for (i, group_info) in self.group_infos.iter_mut() {
-            group_info.start_event = false;
-            group_info.end_event = false;
-            group_info.loop_event = false;
-            group_info.last_amount_in_second = group_info.amount_in_second;
-
-            if group_info.is_playing == true {
-                let group = group_mgr.get_mut(i).unwrap();
-                group.anime(runtime_infos, delta_ms, group_info);
-            }
+            group_info.begin_calc();
 
-            if group_info.start_event {
-                self.group_events.push((i, EAnimationEvent::Start, 0));
-            }
-            if group_info.end_event {
-                self.group_events.push((i, EAnimationEvent::End, 0));
+            match (clock, group_mgr.get_mut(i)) {
+                (Some(clock), Some(group)) => {
+                    let (time_ms, last_time_ms) = match group.time_channel.and_then(|channel| self.time_channels.clock(channel)) {
+                        Some(channel_clock) => channel_clock,
+                        None => clock,
+                    };
+                    if group_info.is_playing == true {
+                        group.anime_at(runtime_infos, time_ms, last_time_ms, group_info);
+                    }
+                    push_group_events(i, Some(group), group_info, &mut self.events);
+                },
+                (None, Some(group)) => {
+                    let is_playing = group_info.is_playing;
+                    let delta_ms = match group.time_channel {
+                        Some(channel) => delta_ms * self.time_channels.effective_scale(channel) as TimeMS,
+                        None => delta_ms,
+                    };
+                    let (count, step_ms, suppress) = group.large_delta.unwrap_or(self.large_delta).steps(delta_ms);
+                    group_info.suppress_event = suppress;
+                    if is_playing {
+                        // 分步推进时 各步的事件标记 和 跨过的循环 累计到本次计算
+                        let last_amount_in_second = group_info.last_amount_in_second;
+                        for _ in 0..count {
+                            group.advance(step_ms, group_info);
+                        }
+                        group_info.last_amount_in_second = last_amount_in_second;
+                    }
+                    push_group_events(i, Some(&mut *group), group_info, &mut self.events);
+                    if is_playing {
+                        group.update_to_infos(runtime_infos);
+                    }
+                },
+                (_, None) => push_group_events::<T>(i, None, group_info, &mut self.events),
             }
-            if group_info.loop_event {
-                self.group_events.push((i, EAnimationEvent::Loop, group_info.looped_count as u32));
+        }
+
+        self.waiters.notify(&self.events, &self.group_infos);
+
+        let mut closed = false;
+        if let Some(sender) = &self.event_sender {
+            for event in self.events.iter() {
+                if sender.send(*event).is_err() {
+                    closed = true;
+                    break;
+                }
             }
         }
+        if closed {
+            self.event_sender = None;
+        }
         // self.group_infos.iter_mut().enumerate().for_each(
         //     |(i, group_info)| {
 
@@ -582,39 +1436,148 @@ impl<T: Clone + PartialEq + Eq + Hash, M: AnimationGroupManager<T>> AnimationCon
         // );
     }
 
+    /// 注册事件监听器 - 返回用于移除的句柄
+    /// * listener.group 为 null (AnimationListener::global) 时监听所有动画组
+    /// * 动画组删除时 其监听器一起移除
+    pub fn add_listener(&mut self, listener: AnimationListener<FrameEventData>) -> AnimationListenerID {
+        self.dispatcher.add(listener)
+    }
+    /// 注册接收事件参数的回调 - 返回用于移除的句柄
+    /// * `group` 为 null 时监听所有动画组
+    pub fn add_event_handler(&mut self, group: AnimationGroupID, call: OnAnimationEvent) -> AnimationListenerID {
+        self.dispatcher.add_handler(group, call)
+    }
+    /// 移除事件监听器 或 回调
+    pub fn remove_listener(&mut self, id: AnimationListenerID) -> bool {
+        self.dispatcher.remove(id)
+    }
+    pub fn listener_mut(&mut self, id: AnimationListenerID) -> Option<&mut AnimationListener<FrameEventData>> {
+        self.dispatcher.get_mut(id)
+    }
+    /// 设置监听 回调出错时的处理方式 - 默认移除出错的回调
+    pub fn set_listener_error_policy(&mut self, id: AnimationListenerID, policy: EListenerErrorPolicy) -> bool {
+        self.dispatcher.set_error_policy(id, policy)
+    }
+    /// 等待动画组结束 - 返回结束原因
+    /// * 在 anime_curve_calc 产生 End Stop 事件 或 动画组被删除时唤醒
+    /// * 动画组未在播放时 立即完成, 结果为最近一次结束的原因
+    pub fn finished(&mut self, id: AnimationGroupID) -> Result<AnimationWait<EAnimationEndReason>, EAnimationError> {
+        match (self.group_mgr.get(id), self.group_infos.get(id)) {
+            (Some(group), Some(_)) => Ok(self.waiters.finished(id, group.is_playing())),
+            _ => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 等待动画组循环次数达到 `count` - 动画组先结束则返回 Err(结束原因)
+    /// * 已达到时 立即完成; 动画组未在播放时 立即返回 Err(最近一次结束的原因)
+    pub fn looped(&mut self, id: AnimationGroupID, count: u32) -> Result<AnimationWait<Result<(), EAnimationEndReason>>, EAnimationError> {
+        match (self.group_mgr.get(id), self.group_infos.get(id)) {
+            (Some(group), Some(info)) => Ok(self.waiters.looped(id, count, group.is_playing(), info.looped_count)),
+            _ => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 等待动画组触发携带 `data` 的帧事件 - 动画组先结束则返回 Err(结束原因)
+    pub fn frame_event_reached(&mut self, id: AnimationGroupID, data: FrameEventData) -> Result<AnimationWait<Result<(), EAnimationEndReason>>, EAnimationError> {
+        match (self.group_mgr.get(id), self.group_infos.get(id)) {
+            (Some(group), Some(_)) => Ok(self.waiters.frame_event_reached(id, data, group.is_playing())),
+            _ => Err(EAnimationError::AnimationGroupNotFound),
+        }
+    }
+    /// 本次计算产生的事件
+    pub fn events(&self) -> &[AnimationEvent] {
+        &self.events
+    }
+    /// 取出本次计算产生的事件 - 需要同时使用 dispatch 时 应在 dispatch 之后调用
+    pub fn drain_events(&mut self) -> std::vec::Drain<'_, AnimationEvent> {
+        self.events.drain(..)
+    }
+    /// 设置事件转发通道 - 每次计算后 事件被复制发送到通道, 供其他线程接收
+    /// * 接收端关闭后 自动取消转发
+    pub fn set_event_sender(&mut self, sender: Option<Sender<AnimationEvent>>) {
+        self.event_sender = sender;
+    }
+    /// 将本次计算的事件 派发给已注册的监听器 - 在 anime_curve_calc 之后调用
+    /// * 回调提交的操作 在派发结束后按顺序执行, 其产生的事件在下次计算时派发
+    /// * 返回 策略为 Propagate 的监听产生的错误 和 操作执行失败的错误 - (动画组, 事件类型, 错误)
+    ///   * 操作执行失败的错误 事件类型为 None
+    pub fn dispatch(&mut self) -> Result<(), Vec<(AnimationGroupID, EAnimationEvent, EAnimationError)>> {
+        let mut commands = AnimationCommands::default();
+        let mut errors = self.dispatcher.dispatch(&self.events, &self.group_infos, &mut commands);
+        commands.drain().for_each(|command| {
+            let group = command.group();
+            match self.apply_command(command) {
+                Ok(_) => {},
+                Err(e) => errors.push((group, EAnimationEvent::None, e)),
+            }
+        });
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+    /// 执行 事件回调提交的操作
+    pub fn apply_command(&mut self, command: EAnimationCommand) -> Result<(), EAnimationError> {
+        match command {
+            EAnimationCommand::StartComplete { group, seconds, param } => {
+                self.start_complete_with(group, seconds, param)
+            },
+            EAnimationCommand::StartWithProgress { group, speed, from, to, param } => {
+                self.start_with_progress_with(group, speed, from, to, param)
+            },
+            EAnimationCommand::Pause(group) => self.pause(group),
+            EAnimationCommand::Stop(group) => self.stop(group),
+            EAnimationCommand::Seek(group, progress) => self.seek(group, progress),
+        }
+    }
+
+    /// 按动画组本次计算的事件标记 调用监听器的回调
+    /// * 出错的回调被移除, 错误与动画组和事件类型一起返回
     pub fn animation_event<E: Clone>(
         &self,
         listener: &mut AnimationListener<E>,
         curve_frame_event: Option<&CurveFrameEvent<E>>,
-    ) {
-        match self.group_infos.get(listener.group) {
+    ) -> Result<(), Vec<(AnimationGroupID, EAnimationEvent, EAnimationError)>> {
+        let id = listener.group;
+        let mut errors = vec![];
+        let mut collect = |ty: EAnimationEvent, result: Result<(), Vec<EAnimationError>>| {
+            if let Err(mut list) = result {
+                list.drain(..).for_each(|e| errors.push((id, ty, e)));
+            }
+        };
+        match self.group_infos.get(id) {
             Some(group_info) => {
                 if group_info.start_event {
-                    listener.on_start();
+                    collect(EAnimationEvent::Start, listener.on_start());
                 }
                 if group_info.end_event {
-                    listener.on_end();
+                    collect(EAnimationEvent::End, listener.on_end());
                 }
-                if group_info.loop_event {
-                    listener.on_loop(group_info.looped_count);
+                if group_info.loop_event && !group_info.suppress_event {
+                    collect(EAnimationEvent::Loop, listener.on_loop(group_info.looped_count));
                 }
                 match curve_frame_event {
-                    Some(frame_event) => {
-                        match frame_event.query(
-                            group_info.last_amount_in_second,
-                            group_info.amount_in_second,
-                        ) {
+                    Some(frame_event) if !group_info.suppress_event => {
+                        let passes = match self.group_mgr.get(id) {
+                            Some(group) => group.amount_passes(group_info),
+                            None => group_info.amount_passes(),
+                        };
+                        match frame_event.query_passes(&passes) {
                             Some(eventdatas) => {
-                                listener.on_frame(eventdatas);
+                                collect(EAnimationEvent::FrameEvent, listener.on_frame(eventdatas));
                             }
                             None => {}
                         }
                     }
-                    None => {}
+                    _ => {}
                 }
             }
             None => {}
         }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
     }
 }
 
@@ -672,4 +1635,167 @@ fn _create_animation(
         // self.curves.push(Some(curve));
         (result, len)
     }
-}
\ No newline at end of file
+}
+
+/// 依据动画组一次推进后的状态 产生事件
+/// * group_info.suppress_event 时 屏蔽 帧事件 和 循环事件
+/// * 帧事件 区间事件 按经过的各次播放依次产生, 每次播放内按时间先后排列, 循环边界处产生 Loop IterationStart
+fn push_group_events<T: Clone + PartialEq + Eq + Hash>(
+    i: AnimationGroupID,
+    group: Option<&mut AnimationGroup<T>>,
+    group_info: &mut AnimationGroupRuntimeInfo,
+    events: &mut Vec<AnimationEvent>,
+) {
+    let suppress = group_info.suppress_event;
+    if group_info.delay_end_event {
+        events.push(AnimationEvent::DelayEnd(i));
+    }
+    if group_info.start_event {
+        events.push(AnimationEvent::Start(i));
+        events.push(AnimationEvent::IterationStart(i, 0));
+    }
+    // 本次计算跨过的循环
+    let loop_last = match group_info.loop_event && !suppress {
+        true => group_info.last_looped_count + group_info.loop_crossed,
+        false => group_info.last_looped_count,
+    };
+    let mut looped = group_info.last_looped_count;
+    let push_loop = |looped: &mut u32, events: &mut Vec<AnimationEvent>| {
+        if *looped < loop_last {
+            *looped += 1;
+            events.push(AnimationEvent::Loop(i, *looped));
+            events.push(AnimationEvent::IterationStart(i, *looped));
+        }
+    };
+    match group {
+        Some(group) => {
+            let passes = group.amount_passes(group_info);
+            let (mut frames, mut ranges) = match group.has_frame_events() {
+                true => {
+                    let frames = match suppress {
+                        true => vec![],
+                        false => group.query_frame_events_timed(&passes),
+                    };
+                    (frames, group.query_range_events_timed(&passes))
+                },
+                false => (vec![], vec![]),
+            };
+            let loop_passes = group.loop_passes();
+            let (mut frames, mut ranges) = (frames.drain(..).peekable(), ranges.drain(..).peekable());
+            let mut last_loop = None;
+            for pass in passes.iter() {
+                let loop_index = pass.index / loop_passes;
+                if let Some(last_loop) = last_loop {
+                    if last_loop != loop_index {
+                        push_loop(&mut looped, events);
+                    }
+                }
+                last_loop = Some(loop_index);
+                // 同一次播放内 按时间先后合并 帧事件 与 区间事件
+                let forward = pass.from <= pass.to;
+                loop {
+                    let frame = match frames.peek() {
+                        Some(item) if item.0 == pass.index => Some(item.1),
+                        _ => None,
+                    };
+                    let range = match ranges.peek() {
+                        Some(item) if item.0 == pass.index => Some(item.1),
+                        _ => None,
+                    };
+                    let take_frame = match (frame, range) {
+                        (Some(frame), Some(range)) => if forward { frame <= range } else { frame >= range },
+                        (Some(_), None) => true,
+                        (None, Some(_)) => false,
+                        (None, None) => break,
+                    };
+                    if take_frame {
+                        if let Some((_, _, data)) = frames.next() {
+                            events.push(AnimationEvent::FrameEvent(i, data));
+                        }
+                    } else if let Some((_, _, ty, data)) = ranges.next() {
+                        events.extend(AnimationEvent::new(i, ty, data as u32));
+                    }
+                }
+            }
+            // 未落在播放进度变化中的循环 - 如循环间隔期间
+            while looped < loop_last {
+                push_loop(&mut looped, events);
+            }
+            if group_info.end_event {
+                group.exit_ranges().drain(..).for_each(|(ty, data)| {
+                    events.extend(AnimationEvent::new(i, ty, data as u32));
+                });
+            }
+        },
+        None => {
+            while looped < loop_last {
+                push_loop(&mut looped, events);
+            }
+        },
+    }
+    if group_info.end_event {
+        events.push(AnimationEvent::End(i));
+    }
+}
+
+/// 使用曲线计算结果 计算属性值 - 返回计算中的错误
+fn _anime_curves<'a, T, F, D, R, G>(
+    runtime_infos: &XHashMap<T, Vec<RuntimeInfo>>,
+    get_curve: G,
+    result_pool: &mut R,
+) -> Vec<EAnimationError>
+where
+    T: Clone + PartialEq + Eq + Hash,
+    F: FrameDataValue,
+    D: AsRef<FrameCurve<F>> + 'a,
+    R: TypeAnimationResultPool<F, T>,
+    G: Fn(FrameCurveInfoID) -> Option<&'a D>,
+{
+    let mut errs = vec![];
+    for (target, info) in runtime_infos {
+        info.iter().for_each(|info| {
+            if let Some(curve) = get_curve(info.curve_id) {
+                // println!(">>>>>>>>>>>>>>>>>{}", info.amount_in_second);
+                let value = curve.as_ref().interple(info.amount_in_second, &info.amount_calc);
+                let result = AnimeResult {
+                    value,
+                    attr: info.attr,
+                    weight: info.group_weight,
+                };
+                match result_pool.record_result(target.clone(), info.attr, result) {
+                    Ok(_) => {}
+                    Err(e) => errs.push(e),
+                }
+            }
+        });
+    }
+    errs
+}
+
+/// 使用曲线计算结果 计算属性值 - 不检查曲线是否存在
+fn _anime_curves_uncheck<'a, T, F, D, R, G>(
+    runtime_infos: &XHashMap<T, Vec<RuntimeInfo>>,
+    get_curve: G,
+    result_pool: &mut R,
+)
+where
+    T: Clone + PartialEq + Eq + Hash,
+    F: FrameDataValue,
+    D: AsRef<FrameCurve<F>> + 'a,
+    R: TypeAnimationResultPool<F, T>,
+    G: Fn(FrameCurveInfoID) -> &'a D,
+{
+    for (target, info) in runtime_infos {
+        info.iter().for_each(|info| {
+            let curve = get_curve(info.curve_id);
+            // println!(">>>>>>>>>>>>>>>>>{}", info.amount_in_second);
+            let value = curve.as_ref().interple(info.amount_in_second, &info.amount_calc);
+            let result = AnimeResult {
+                value,
+                attr: info.attr,
+                weight: info.group_weight,
+            };
+            let _ = result_pool.record_result(target.clone(), info.attr, result);
+        });
+    }
+}
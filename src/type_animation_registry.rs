@@ -0,0 +1,283 @@
+use std::{any::Any, hash::Hash};
+
+use pi_curves::curve::{
+    frame::{FrameDataValue, KeyFrameDataType, KeyFrameDataTypeAllocator},
+    frame_curve::FrameCurve,
+};
+
+use crate::{
+    animation::AnimationInfo,
+    animation_group::AnimationGroupID,
+    animation_group_manager::AnimationGroupManager,
+    animation_result_pool::TypeAnimationResultPool,
+    error::EAnimationError,
+    base::TimeMS,
+    frame_curve_manager::{SharedFrameCurveInfoManager, SharedFrameCurvePool},
+    runtime_info::RuntimeInfoMap,
+    type_animation_context::{AnimationContextAmount, TypeAnimationContext},
+};
+
+/// 类型擦除后的 类型动画上下文
+/// * 由 TypeAnimationRegistry 统一调度
+pub trait TTypeAnimationContext<T: Clone + PartialEq + Eq + Hash> {
+    fn ty(&self) -> KeyFrameDataType;
+    /// 使用曲线计算结果 计算属性值
+    fn anime(&mut self, runtime_infos: &RuntimeInfoMap<T>) -> Result<(), Vec<EAnimationError>>;
+    /// 动画中间数据清理
+    fn reset(&mut self);
+    /// 移除动画对应的曲线信息
+    fn remove_one(&mut self, animation: &AnimationInfo);
+    /// 记录动画目标
+    fn record_target(&mut self, target: T);
+    /// 移除动画目标的记录
+    fn remove_target(&mut self, target: T);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+/// 一种数据类型的 类型动画上下文 及其 动画结果池
+pub struct TypeAnimationUnit<F: FrameDataValue, D: AsRef<FrameCurve<F>>, R> {
+    pub context: TypeAnimationContext<F, D>,
+    pub result_pool: R,
+}
+
+impl<T, F, D, R> TTypeAnimationContext<T> for TypeAnimationUnit<F, D, R>
+where
+    T: Clone + PartialEq + Eq + Hash,
+    F: FrameDataValue + 'static,
+    D: AsRef<FrameCurve<F>> + 'static,
+    R: TypeAnimationResultPool<F, T> + 'static,
+{
+    fn ty(&self) -> KeyFrameDataType {
+        self.context.ty()
+    }
+    fn anime(&mut self, runtime_infos: &RuntimeInfoMap<T>) -> Result<(), Vec<EAnimationError>> {
+        self.context.anime(runtime_infos, &mut self.result_pool)
+    }
+    fn reset(&mut self) {
+        self.result_pool.reset();
+    }
+    fn remove_one(&mut self, animation: &AnimationInfo) {
+        self.context.remove_one(animation);
+    }
+    fn record_target(&mut self, target: T) {
+        self.result_pool.record_target(target);
+    }
+    fn remove_target(&mut self, target: T) {
+        self.result_pool.remove_target(target);
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// 多数据类型的 动画注册表
+/// * 分配 KeyFrameDataType, 持有每种数据类型的 类型动画上下文 和 动画结果池
+/// * anime 一次调用完成 中间数据清理、已移除动画的应用、曲线计算 和 各类型的属性值计算
+pub struct TypeAnimationRegistry<T: Clone + PartialEq + Eq + Hash> {
+    pub runtime_infos: RuntimeInfoMap<T>,
+    ty_allocator: KeyFrameDataTypeAllocator,
+    /// 按 KeyFrameDataType 索引 - 注册失败的数据类型 对应 None
+    contexts: Vec<Option<Box<dyn TTypeAnimationContext<T>>>>,
+}
+
+impl<T: Clone + PartialEq + Eq + Hash + 'static> Default for TypeAnimationRegistry<T> {
+    fn default() -> Self {
+        Self {
+            runtime_infos: RuntimeInfoMap::default(),
+            ty_allocator: KeyFrameDataTypeAllocator::default(),
+            contexts: vec![],
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + Eq + Hash + 'static> TypeAnimationRegistry<T> {
+    /// 注册一种数据类型 - 分配 KeyFrameDataType
+    pub fn register<F, D, R>(
+        &mut self,
+        result_pool: R,
+    ) -> Result<KeyFrameDataType, EAnimationError>
+    where
+        F: FrameDataValue + 'static,
+        D: AsRef<FrameCurve<F>> + 'static,
+        R: TypeAnimationResultPool<F, T> + 'static,
+    {
+        let ty = match self.ty_allocator.alloc() {
+            Ok(ty) => ty,
+            Err(_) => return Err(EAnimationError::KeyAnimeDataTypeCannotAllocMore),
+        };
+        let context = TypeAnimationContext::<F, D>::new(ty, &mut self.runtime_infos);
+        self.insert(ty, Box::new(TypeAnimationUnit { context, result_pool }));
+        Ok(ty)
+    }
+    /// 注册一种数据类型 - 曲线保存在外部共享的曲线存储中
+    /// * 参见 TypeAnimationContext::new_in_store
+    pub fn register_in_store<F, D, R>(
+        &mut self,
+        result_pool: R,
+        curve_infos: SharedFrameCurveInfoManager,
+        curve_pool: SharedFrameCurvePool<F, D>,
+    ) -> Result<KeyFrameDataType, EAnimationError>
+    where
+        F: FrameDataValue + 'static,
+        D: AsRef<FrameCurve<F>> + 'static,
+        R: TypeAnimationResultPool<F, T> + 'static,
+    {
+        let ty = match self.ty_allocator.alloc() {
+            Ok(ty) => ty,
+            Err(_) => return Err(EAnimationError::KeyAnimeDataTypeCannotAllocMore),
+        };
+        match TypeAnimationContext::<F, D>::new_in_store(ty, &mut self.runtime_infos, curve_infos, curve_pool) {
+            Ok(context) => {
+                self.insert(ty, Box::new(TypeAnimationUnit { context, result_pool }));
+                Ok(ty)
+            },
+            Err(e) => Err(e),
+        }
+    }
+    fn insert(&mut self, ty: KeyFrameDataType, context: Box<dyn TTypeAnimationContext<T>>) {
+        if ty >= self.contexts.len() {
+            for _ in self.contexts.len()..ty + 1 {
+                self.contexts.push(None);
+            }
+        }
+        self.contexts[ty] = Some(context);
+    }
+    /// 已注册的数据类型数目
+    pub fn len(&self) -> usize {
+        self.contexts.iter().filter(|item| item.is_some()).count()
+    }
+    /// 是否没有注册任何数据类型
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// 获取 指定数据类型 的类型动画上下文及动画结果池
+    /// * 类型参数 与注册时不一致则返回 None
+    pub fn get<F, D, R>(
+        &self,
+        ty: KeyFrameDataType,
+    ) -> Option<&TypeAnimationUnit<F, D, R>>
+    where
+        F: FrameDataValue + 'static,
+        D: AsRef<FrameCurve<F>> + 'static,
+        R: TypeAnimationResultPool<F, T> + 'static,
+    {
+        match self.contexts.get(ty) {
+            Some(Some(item)) => item.as_any().downcast_ref::<TypeAnimationUnit<F, D, R>>(),
+            _ => None,
+        }
+    }
+    /// 获取 指定数据类型 的类型动画上下文及动画结果池
+    /// * 类型参数 与注册时不一致则返回 None
+    pub fn get_mut<F, D, R>(
+        &mut self,
+        ty: KeyFrameDataType,
+    ) -> Option<&mut TypeAnimationUnit<F, D, R>>
+    where
+        F: FrameDataValue + 'static,
+        D: AsRef<FrameCurve<F>> + 'static,
+        R: TypeAnimationResultPool<F, T> + 'static,
+    {
+        match self.contexts.get_mut(ty) {
+            Some(Some(item)) => item.as_any_mut().downcast_mut::<TypeAnimationUnit<F, D, R>>(),
+            _ => None,
+        }
+    }
+    /// 为所有数据类型的动画结果池 记录动画目标
+    pub fn record_target(&mut self, target: T) {
+        self.contexts.iter_mut().flatten().for_each(|item| {
+            item.record_target(target.clone());
+        });
+    }
+    /// 移除 目标 的所有动画, 并清理各数据类型的动画结果池中 目标的记录
+    /// * 返回被删除的动画组
+    pub fn remove_target<M: AnimationGroupManager<T>>(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<T, M>,
+        target: T,
+    ) -> Vec<AnimationGroupID> {
+        let result = animation_context_amount.remove_target(&target);
+        self.contexts.iter_mut().flatten().for_each(|item| {
+            item.remove_target(target.clone());
+        });
+        result
+    }
+    /// 动画中间数据清理
+    pub fn reset(&mut self) {
+        self.runtime_infos.reset();
+        self.contexts.iter_mut().flatten().for_each(|item| {
+            item.reset();
+        });
+    }
+    /// 在各数据类型的上下文 应用 动画的移除记录, 并清空记录
+    pub fn apply_removed_animations<M: AnimationGroupManager<T>>(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<T, M>,
+    ) {
+        animation_context_amount.removed_animations.iter().for_each(|anime| {
+            if let Some(Some(item)) = self.contexts.get_mut(anime.ty) {
+                item.remove_one(anime);
+            }
+        });
+        animation_context_amount.clear_removed_animations();
+    }
+    /// 运行动画
+    /// * 清理中间数据 - 应用已移除动画 - 曲线计算 - 各数据类型的属性值计算
+    pub fn anime<M: AnimationGroupManager<T>>(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<T, M>,
+        delta_ms: u64,
+    ) -> Result<(), Vec<EAnimationError>> {
+        self.reset();
+        self.apply_removed_animations(animation_context_amount);
+
+        animation_context_amount.anime_curve_calc(delta_ms, &mut self.runtime_infos);
+
+        self.anime_contexts()
+    }
+    /// 运行动画 - 间隔时间可以是小数毫秒
+    pub fn anime_ms<M: AnimationGroupManager<T>>(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<T, M>,
+        delta_ms: TimeMS,
+    ) -> Result<(), Vec<EAnimationError>> {
+        self.reset();
+        self.apply_removed_animations(animation_context_amount);
+
+        animation_context_amount.anime_curve_calc_ms(delta_ms, &mut self.runtime_infos);
+
+        self.anime_contexts()
+    }
+    /// 以绝对时钟运行动画
+    /// * `time_second` 当前时刻 - 秒, 参见 AnimationContextAmount::anime_curve_calc_at
+    pub fn anime_at<M: AnimationGroupManager<T>>(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<T, M>,
+        time_second: f64,
+    ) -> Result<(), Vec<EAnimationError>> {
+        self.reset();
+        self.apply_removed_animations(animation_context_amount);
+
+        animation_context_amount.anime_curve_calc_at(time_second, &mut self.runtime_infos);
+
+        self.anime_contexts()
+    }
+    /// 各数据类型的属性值计算
+    fn anime_contexts(&mut self) -> Result<(), Vec<EAnimationError>> {
+        let mut errs = vec![];
+        for item in self.contexts.iter_mut().flatten() {
+            if let Err(mut list) = item.anime(&self.runtime_infos) {
+                errs.append(&mut list);
+            }
+        }
+
+        if !errs.is_empty() {
+            Err(errs)
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -147,6 +147,22 @@ impl TypeAnimationContextMgr {
         r0
     }
     
+    /// 以绝对时钟运行动画
+    pub fn anime_at(
+        &mut self,
+        animation_context_amount: &mut AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>,
+        time_second: f64,
+    ) -> Result<(), Vec<EAnimationError>> {
+        self.reset();
+
+        animation_context_amount.anime_curve_calc_at(time_second, &mut self.runtime_infos);
+
+        let r0 = self.value0_ctx.anime(&self.runtime_infos, &mut self.value0_result_pool);
+        let _ = self.value1_ctx.anime(&self.runtime_infos, &mut self.value1_result_pool);
+        let _ = self.f32_ctx.anime(&self.runtime_infos, &mut self.f32_result_pool);
+        r0
+    }
+
     /// 运行动画
     pub fn anime_uncheck(
         &mut self,
@@ -193,13 +209,16 @@ impl<F: FrameDataValue> AsRef<FrameCurve<F>> for AssetCurve<F> {
 
 #[cfg(test)]
 mod test01 {
-    use std::{sync::Arc, mem::replace};
+    use std::{sync::{Arc, Mutex}, mem::replace};
 
     use pi_animation::{type_animation_context::{AnimationContextAmount}, target_modifier::{IDAnimatableTargetAllocator, TAnimatableTargetModifier, IDAnimatableAttr, IDAnimatableTarget, TAnimatableTargetId}, loop_mode::ELoopMode, animation_listener::{AnimationListener, EAnimationEventResult}, curve_frame_event::CurveFrameEvent, amount::AnimationAmountCalc, animation_group_manager::AnimationGroupManagerDefault, base::EFillMode};
     use pi_curves::{curve::{frame_curve::FrameCurve, FrameIndex, frame::KeyFrameCurveValue}, easing::EEasingMode, steps::EStepMode};
     use pi_slotmap::{SlotMap, DefaultKey};
+    use pi_null::Null;
     use test::{Bencher};
 
+    use pi_animation::{type_animation_context::TypeAnimationContext, runtime_info::RuntimeInfoMap, animation_result_pool::{TypeAnimationResultPoolDefault, TypeAnimationResultPool}, frame_curve_manager::{FrameCurveInfoManager, FrameCurvePool, TFrameCurvePool, SharedFrameCurveInfoManager}, type_animation_registry::TypeAnimationRegistry};
+
     use crate::{TypeAnimationContextMgr, Value0, Target0, Target0AnimatableAttrSet, AssetCurve};
 
     #[test]
@@ -377,6 +396,7 @@ mod test01 {
                 });
                 Ok(EAnimationEventResult::None)
             })],
+            ..Default::default()
         };
         
         for i in 0..100 {
@@ -391,8 +411,8 @@ mod test01 {
 
             type_animation_ctx_mgr.runtime_infos.reset();
             animation_context_amount.anime_curve_calc(30, &mut type_animation_ctx_mgr.runtime_infos);
-            for (group_id, ty, count) in animation_context_amount.group_events.iter() {
-                println!("AG: {:?}, {:?}, {:?}", group_id, ty, count);
+            for event in animation_context_amount.events().iter() {
+                println!("AG: {:?}", event);
             }
         }
 
@@ -453,6 +473,7 @@ mod test01 {
                 });
                 Ok(EAnimationEventResult::None)
             })],
+            ..Default::default()
         };
 
         for i in 0..300 {
@@ -501,17 +522,24 @@ mod test01 {
             for i in 0..1 {
                 type_animation_ctx_mgr.runtime_infos.reset();
                 animation_context_amount.anime_curve_calc(200, &mut type_animation_ctx_mgr.runtime_infos);
-                let mut list = replace(&mut animation_context_amount.group_events, vec![]);
-                for (group_id, ty, count) in list.iter() {
-                    println!("AG 0: {:?}, {:?}, {:?}", group_id, ty, count);
-                    match ty {
-                        pi_animation::animation_listener::EAnimationEvent::None => {},
-                        pi_animation::animation_listener::EAnimationEvent::Start => {},
-                        pi_animation::animation_listener::EAnimationEvent::End => {
+                let list: Vec<_> = animation_context_amount.drain_events().collect();
+                for event in list.iter() {
+                    println!("AG 0: {:?}", event);
+                    match event {
+                        pi_animation::animation_listener::AnimationEvent::Start(_) => {},
+                        pi_animation::animation_listener::AnimationEvent::End(group_id) => {
                             animation_context_amount.del_animation_group(*group_id);
                         },
-                        pi_animation::animation_listener::EAnimationEvent::Loop => {},
-                        pi_animation::animation_listener::EAnimationEvent::FrameEvent => {},
+                        pi_animation::animation_listener::AnimationEvent::Loop(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::FrameEvent(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::RangeEnter(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::RangeExit(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::Pause(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::Stop(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::Cancel(_, _, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::Remove(_, _) => {},
+                        pi_animation::animation_listener::AnimationEvent::DelayEnd(_) => {},
+                        pi_animation::animation_listener::AnimationEvent::IterationStart(_, _) => {},
                     }
                 }
 
@@ -583,23 +611,1584 @@ mod test01 {
             
             type_animation_ctx_mgr.runtime_infos.reset();
             animation_context_amount.anime_curve_calc(40, &mut type_animation_ctx_mgr.runtime_infos);
-            let mut list = replace(&mut animation_context_amount.group_events, vec![]);
-            for (group_id, ty, count) in list.iter() {
-                println!("AG 1: {:?}, {:?}, {:?}", group_id, ty, count);
-                match ty {
-                    pi_animation::animation_listener::EAnimationEvent::None => {},
-                    pi_animation::animation_listener::EAnimationEvent::Start => {},
-                    pi_animation::animation_listener::EAnimationEvent::End => {
+            let list: Vec<_> = animation_context_amount.drain_events().collect();
+            for event in list.iter() {
+                println!("AG 1: {:?}", event);
+                match event {
+                    pi_animation::animation_listener::AnimationEvent::Start(_) => {},
+                    pi_animation::animation_listener::AnimationEvent::End(group_id) => {
                         animation_context_amount.del_animation_group(*group_id);
                     },
-                    pi_animation::animation_listener::EAnimationEvent::Loop => {},
-                    pi_animation::animation_listener::EAnimationEvent::FrameEvent => {},
+                    pi_animation::animation_listener::AnimationEvent::Loop(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::FrameEvent(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::RangeEnter(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::RangeExit(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::Pause(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::Stop(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::Cancel(_, _, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::Remove(_, _) => {},
+                    pi_animation::animation_listener::AnimationEvent::DelayEnd(_) => {},
+                    pi_animation::animation_listener::AnimationEvent::IterationStart(_, _) => {},
                 }
             }
         }
 
     }
 
+    #[test]
+    fn test_curve_share() {
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+
+        // 创建动画曲线
+        let frame_count = 30 as FrameIndex;
+        let curve0 = FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None);
+        let curve0 = crate::AssetCurve::<f32>(Arc::new(curve0));
+        let curve1 = FrameCurve::curve_easing(0.0f32, 1.0f32, frame_count, frame_count, EEasingMode::None);
+        let curve1 = crate::AssetCurve::<f32>(Arc::new(curve1));
+
+        // 同一曲线资产 只保存一份
+        let ctx = &mut type_animation_ctx_mgr.f32_ctx;
+        let animation0 = ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve0.clone());
+        let animation1 = ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve0.clone());
+        let animation2 = ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve1.clone());
+        assert_eq!(animation0.curve_id, animation1.curve_id);
+        assert_ne!(animation0.curve_id, animation2.curve_id);
+        assert_eq!(ctx.curve_id(&curve0), Some(animation0.curve_id));
+        assert_eq!(ctx.curve_ref_count(animation0.curve_id), 2);
+        assert_eq!(ctx.curve_count(), 2);
+
+        // 最后一个引用移除时 才释放曲线
+        ctx.remove_one(&animation0);
+        assert_eq!(ctx.curve_ref_count(animation1.curve_id), 1);
+        assert!(ctx.curve(animation1.curve_id).is_some());
+
+        ctx.remove_one(&animation1);
+        assert_eq!(ctx.curve_ref_count(animation1.curve_id), 0);
+        assert!(ctx.curve(animation1.curve_id).is_none());
+        assert_eq!(ctx.curve_id(&curve0), None);
+        assert_eq!(ctx.curve_count(), 1);
+    }
+
+    #[test]
+    fn test_replace_curve() {
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+
+        // 创建动画曲线 - 30 帧
+        let frame_count = 30 as FrameIndex;
+        let curve0 = FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None);
+        let curve0 = crate::AssetCurve::<f32>(Arc::new(curve0));
+        let animation0 = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve0.clone());
+        let curve_id = animation0.curve_id;
+
+        let group0 = animation_context_amount.create_animation_group();
+        animation_context_amount.add_target_animation_notype(animation0, group0, target.anime_target_id()).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        let max_frame = animation_context_amount.animation_group(group0).unwrap().max_frame();
+
+        // 替换为 60 帧 的曲线
+        let curve1 = FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count * 2, frame_count, EEasingMode::None);
+        let curve1 = crate::AssetCurve::<f32>(Arc::new(curve1));
+        let groups = animation_context_amount.replace_curve(&mut type_animation_ctx_mgr.f32_ctx, curve_id, curve1.clone()).unwrap();
+        assert_eq!(groups, vec![group0]);
+        assert_eq!(animation_context_amount.animation_group(group0).unwrap().max_frame(), max_frame * 2.);
+        assert_eq!(type_animation_ctx_mgr.f32_ctx.curve_id(&curve1), Some(curve_id));
+        assert_eq!(type_animation_ctx_mgr.f32_ctx.curve_id(&curve0), None);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+
+        // 运行中替换曲线 按归一化进度保持位置, 替换前后之间的帧事件 不触发
+        let curve2 = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let animation1 = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V1 as IDAnimatableAttr, curve2.clone());
+        let curve_id = animation1.curve_id;
+        let group1 = animation_context_amount.create_animation_group();
+        animation_context_amount.add_target_animation_notype(animation1, group1, target.anime_target_id()).unwrap();
+        animation_context_amount.add_frame_event(group1, 15., frame_count, 1).unwrap();
+        // 替换后曲线 50% 的位置
+        animation_context_amount.add_frame_event(group1, 30., frame_count, 2).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        let frame_events = |animation_context_amount: &mut AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, type_animation_ctx_mgr: &mut TypeAnimationContextMgr, delta: u64| {
+            type_animation_ctx_mgr.anime(animation_context_amount, delta).unwrap();
+            let mut datas = vec![];
+            animation_context_amount.events().iter().for_each(|event| {
+                if let pi_animation::animation_listener::AnimationEvent::FrameEvent(id, data) = event {
+                    if *id == group1 {
+                        datas.push(*data);
+                    }
+                }
+            });
+            datas
+        };
+        let mut events = vec![];
+        for _ in 0..10 {
+            events.append(&mut frame_events(&mut animation_context_amount, &mut type_animation_ctx_mgr, 100));
+        }
+        assert_eq!(events, vec![1]);
+
+        // 进度约 90% 时 替换为 两倍长度 的曲线
+        let curve3 = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count * 2, frame_count, EEasingMode::None)));
+        animation_context_amount.replace_curve(&mut type_animation_ctx_mgr.f32_ctx, curve_id, curve3.clone()).unwrap();
+        let mut events = vec![];
+        for _ in 0..2 {
+            events.append(&mut frame_events(&mut animation_context_amount, &mut type_animation_ctx_mgr, 50));
+        }
+        assert_eq!(events, vec![]);
+    }
+
+    #[test]
+    fn test_curve_store() {
+        let mut runtime_infos = RuntimeInfoMap::<DefaultKey>::default();
+        let curve_infos: SharedFrameCurveInfoManager = Arc::new(Mutex::new(FrameCurveInfoManager::default()));
+        let curve_pool = Arc::new(Mutex::new(FrameCurvePool::<f32, AssetCurve<f32>>::default()));
+        let mut result_pool = TypeAnimationResultPoolDefault::<f32>::default();
+        let mut target_allocator = SlotMap::<DefaultKey, ()>::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        // 曲线保存在共享的曲线存储中
+        let mut f32_ctx = TypeAnimationContext::<f32, AssetCurve<f32>>::new_in_store(0, &mut runtime_infos, curve_infos.clone(), curve_pool.clone()).unwrap();
+        assert!(f32_ctx.in_store());
+        let frame_count = 30 as FrameIndex;
+        let curve = AssetCurve(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let curve_id = f32_ctx.insert_curve_to_store(curve).unwrap();
+
+        let target0 = target_allocator.insert(());
+        let target1 = target_allocator.insert(());
+        let target2 = target_allocator.insert(());
+        result_pool.record_target(target0);
+        result_pool.record_target(target1);
+        result_pool.record_target(target2);
+
+        // 多个动画使用同一曲线
+        let group0 = animation_context_amount.create_animation_group();
+        let animation0 = f32_ctx.create_animation_from_store(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve_id).unwrap();
+        let animation1 = f32_ctx.create_animation_from_store(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve_id).unwrap();
+        // 包含 insert_curve_to_store 时调用方持有的引用
+        assert_eq!(f32_ctx.curve_ref_count(curve_id), 3);
+        assert_eq!(f32_ctx.with_curve(curve_id, |curve| curve.0.max_frame), Some(frame_count));
+        // create_animation 同样使用共享存储, 同一资产只保存一份
+        let curve1 = AssetCurve(Arc::new(FrameCurve::curve_easing(0.0f32, 50.0f32, frame_count, frame_count, EEasingMode::None)));
+        let animation2 = f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, AssetCurve(curve1.0.clone()));
+        assert!(f32_ctx.curves().is_empty());
+        assert_eq!(f32_ctx.curve_id(&curve1), Some(animation2.curve_id));
+        assert_eq!(f32_ctx.curve_ref_count(animation2.curve_id), 1);
+        assert_eq!(f32_ctx.curve_count(), 1);
+        let curve1_id = animation2.curve_id;
+        animation_context_amount.add_target_animation_notype(animation0, group0, target0).unwrap();
+        animation_context_amount.add_target_animation_notype(animation1, group0, target1).unwrap();
+        animation_context_amount.add_target_animation_notype(animation2, group0, target2).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        animation_context_amount.anime_curve_calc(100, &mut runtime_infos);
+        f32_ctx.anime(&runtime_infos, &mut result_pool).unwrap();
+        assert_eq!(result_pool.query_result(target0).len(), 1);
+        assert_eq!(result_pool.query_result(target1).len(), 1);
+        assert_eq!(result_pool.query_result(target2).len(), 1);
+
+        // 仍被动画使用的曲线 不可移除
+        assert_eq!(f32_ctx.remove_curve_from_store(curve_id), Err(pi_animation::error::EAnimationError::FrameCurveInUse));
+
+        // 移除动画组 减少共享存储中的引用计数
+        animation_context_amount.del_animation_group(group0);
+        animation_context_amount.removed_animations.iter().for_each(|anime| f32_ctx.remove_one(anime));
+        animation_context_amount.clear_removed_animations();
+        assert_eq!(f32_ctx.curve_ref_count(curve_id), 1);
+        // create_animation 添加的曲线 随最后一个动画释放
+        assert_eq!(f32_ctx.curve_count(), 0);
+        assert_eq!(f32_ctx.curve_id(&curve1), None);
+        assert!(curve_infos.lock().unwrap().get(0, curve1_id).is_err());
+        // insert_curve_to_store 添加的曲线 由调用方移除
+        assert!(curve_infos.lock().unwrap().get(0, curve_id).is_ok());
+        f32_ctx.remove_curve_from_store(curve_id).unwrap();
+        assert!(curve_infos.lock().unwrap().get(0, curve_id).is_err());
+        assert!(curve_pool.lock().unwrap().get(curve_id).is_err());
+        assert_eq!(f32_ctx.with_curve(curve_id, |curve| curve.0.max_frame), None);
+
+        // 多个上下文共享存储 - 曲线由最后释放引用的上下文移除
+        let mut f32_ctx_b = TypeAnimationContext::<f32, AssetCurve<f32>>::new_in_store(0, &mut runtime_infos, curve_infos.clone(), curve_pool.clone()).unwrap();
+        let animation_a = f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, AssetCurve(curve1.0.clone()));
+        let animation_b = f32_ctx_b.create_animation_from_store(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, animation_a.curve_id).unwrap();
+        assert_eq!(f32_ctx_b.curve_ref_count(animation_a.curve_id), 2);
+        f32_ctx.remove_one(&animation_a);
+        assert!(curve_pool.lock().unwrap().get(animation_a.curve_id).is_ok());
+        f32_ctx_b.remove_one(&animation_b);
+        assert!(curve_infos.lock().unwrap().get(0, animation_a.curve_id).is_err());
+        assert!(curve_pool.lock().unwrap().get(animation_a.curve_id).is_err());
+        assert_eq!(f32_ctx.curve_id(&curve1), None);
+        assert_eq!(f32_ctx.curve_count(), 0);
+    }
+
+    #[test]
+    fn test_registry() {
+        let mut registry = TypeAnimationRegistry::<DefaultKey>::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        let mut target_allocator = SlotMap::<DefaultKey, ()>::default();
+
+        // 注册数据类型
+        assert!(registry.is_empty());
+        let ty_f32 = registry.register::<f32, AssetCurve<f32>, TypeAnimationResultPoolDefault<f32>>(TypeAnimationResultPoolDefault::default()).unwrap();
+        let ty_value0 = registry.register::<Value0, AssetCurve<Value0>, TypeAnimationResultPoolDefault<Value0>>(TypeAnimationResultPoolDefault::default()).unwrap();
+        assert_ne!(ty_f32, ty_value0);
+        assert_eq!(registry.len(), 2);
+        assert_ne!(ty_f32, ty_value0);
+        assert!(registry.get::<Value0, AssetCurve<Value0>, TypeAnimationResultPoolDefault<Value0>>(ty_f32).is_none());
+
+        let target = target_allocator.insert(());
+        registry.record_target(target);
+
+        let frame_count = 30 as FrameIndex;
+        let curve0 = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let curve1 = crate::AssetCurve::<Value0>(Arc::new(FrameCurve::curve_easing(Value0(0.0), Value0(1.0), frame_count, frame_count, EEasingMode::None)));
+        let animation0 = registry.get_mut::<f32, AssetCurve<f32>, TypeAnimationResultPoolDefault<f32>>(ty_f32).unwrap().context.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve0);
+        let animation1 = registry.get_mut::<Value0, AssetCurve<Value0>, TypeAnimationResultPoolDefault<Value0>>(ty_value0).unwrap().context.create_animation(Target0AnimatableAttrSet::V0 as IDAnimatableAttr, curve1);
+
+        let group0 = animation_context_amount.create_animation_group();
+        animation_context_amount.add_target_animation_notype(animation0, group0, target).unwrap();
+        animation_context_amount.add_target_animation_notype(animation1, group0, target).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        // 一次调用 计算所有类型的动画
+        registry.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(registry.get_mut::<f32, AssetCurve<f32>, TypeAnimationResultPoolDefault<f32>>(ty_f32).unwrap().result_pool.query_result(target).len(), 1);
+        assert_eq!(registry.get_mut::<Value0, AssetCurve<Value0>, TypeAnimationResultPoolDefault<Value0>>(ty_value0).unwrap().result_pool.query_result(target).len(), 1);
+
+        // 删除动画组后 下一次运行时释放曲线
+        animation_context_amount.del_animation_group(group0);
+        registry.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(registry.get::<f32, AssetCurve<f32>, TypeAnimationResultPoolDefault<f32>>(ty_f32).unwrap().context.curve_count(), 0);
+        assert_eq!(registry.get::<Value0, AssetCurve<Value0>, TypeAnimationResultPoolDefault<Value0>>(ty_value0).unwrap().context.curve_count(), 0);
+    }
+
+    #[test]
+    fn test_remove_target() {
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target0 = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let target1 = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        // group0 只作用于 target0, group1 作用于 target0 和 target1
+        let group0 = animation_context_amount.create_animation_group();
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target0.anime_target_id()).unwrap();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target0.anime_target_id()).unwrap();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target1.anime_target_id()).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        assert_eq!(animation_context_amount.target_groups(&target0.anime_target_id()).unwrap().len(), 2);
+
+        let removed = animation_context_amount.remove_target(&target0.anime_target_id());
+        assert_eq!(removed, vec![group0]);
+        assert!(animation_context_amount.animation_group(group0).is_none());
+        assert_eq!(animation_context_amount.animation_group(group1).unwrap().animations().len(), 1);
+        assert_eq!(animation_context_amount.removed_animations.len(), 2);
+        assert!(animation_context_amount.target_groups(&target0.anime_target_id()).is_none());
+
+        animation_context_amount.apply_removed_animations(&mut type_animation_ctx_mgr.f32_ctx);
+        animation_context_amount.clear_removed_animations();
+        assert_eq!(type_animation_ctx_mgr.f32_ctx.curve_ref_count(0), 1);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(type_animation_ctx_mgr.f32_result_pool.query_result(target1.anime_target_id()).len(), 1);
+    }
+
+    #[test]
+    fn test_frame_event_passes() {
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.debug(true);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        // 首帧 中间帧 尾帧 事件
+        let mut curve_frame_event = CurveFrameEvent::<FrameIndex>::new(30.);
+        curve_frame_event.add(0, 0);
+        curve_frame_event.add(15, 15);
+        curve_frame_event.add(30, 30);
+
+        // 正向循环 3 次, 一次计算跨越多次循环
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        animation_context_amount.debug(true);
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(3)), frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        let mut events = vec![];
+        for delta in [2500, 10000, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            let passes = animation_context_amount.group_infos.get(group0).unwrap().amount_passes();
+            events.push(curve_frame_event.query_passes(&passes).unwrap_or(vec![]));
+        }
+        assert_eq!(events, vec![vec![0], vec![15, 30, 0, 15, 30, 0, 15], vec![30]]);
+
+        // 反向播放
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target.anime_target_id()).unwrap();
+        animation_context_amount.debug(true);
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Opposite(Some(1)), frame_count, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        let mut events = vec![];
+        for delta in [2500, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            let passes = animation_context_amount.group_infos.get(group1).unwrap().amount_passes();
+            events.push(curve_frame_event.query_passes(&passes).unwrap_or(vec![]));
+        }
+        assert_eq!(events, vec![vec![30], vec![15, 0]]);
+    }
+
+    #[test]
+    fn test_group_frame_event() {
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+
+        // 帧事件以曲线时间描述, 与播放速度和播放区间无关
+        animation_context_amount.add_frame_event(group0, 15., frame_count, 1).unwrap();
+        animation_context_amount.add_frame_event_ms(group0, 900., 2).unwrap();
+        animation_context_amount.add_target_frame_event(group0, &target.anime_target_id(), Target0AnimatableAttrSet::V2 as IDAnimatableAttr, 30., frame_count, 3).unwrap();
+        assert!(animation_context_amount.add_target_frame_event(group0, &target.anime_target_id(), Target0AnimatableAttrSet::V1 as IDAnimatableAttr, 30., frame_count, 3).is_err());
+        assert!(animation_context_amount.add_frame_event(group0, 15., 0, 1).is_err());
+
+        // 两倍速 播放后半段 循环 2 次
+        animation_context_amount.debug(true);
+        animation_context_amount.start_with_progress(group0, 2.0, ELoopMode::Positive(Some(2)), 0.5, 1.0, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        let mut events = vec![];
+        for delta in [100, 100, 10000, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            let mut datas = vec![];
+            animation_context_amount.events().iter().for_each(|event| {
+                if let pi_animation::animation_listener::AnimationEvent::FrameEvent(id, data) = event {
+                    if *id == group0 {
+                        datas.push(*data);
+                    }
+                }
+            });
+            events.push(datas);
+        }
+        assert_eq!(events, vec![vec![1], vec![], vec![2], vec![3, 1, 2, 3]]);
+
+        // 移除 Target动画 后其帧事件一起移除
+        animation_context_amount.remove_target(&target.anime_target_id());
+        assert!(animation_context_amount.remove_frame_event(group0, 1).is_err());
+    }
+
+    #[test]
+    fn test_range_event() {
+        use pi_animation::animation_listener::AnimationEvent;
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        let range_events = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, group: DefaultKey| {
+            let mut result = vec![];
+            ctx.events().iter().for_each(|event| {
+                match event {
+                    AnimationEvent::RangeEnter(id, data) if *id == group => result.push((true, *data)),
+                    AnimationEvent::RangeExit(id, data) if *id == group => result.push((false, *data)),
+                    _ => {},
+                }
+            });
+            result
+        };
+
+        let mut groups = vec![];
+        for loop_mode in [ELoopMode::Not, ELoopMode::Not, ELoopMode::Opposite(Some(1))] {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            animation_context_amount.add_range_event(group, 6., 12., frame_count, 1).unwrap();
+            animation_context_amount.add_range_event_ms(group, 600., 800., 2).unwrap();
+            groups.push((group, loop_mode));
+        }
+        animation_context_amount.debug(true);
+
+        // 逐帧经过 跳转 停止
+        let (group0, loop_mode) = groups[0];
+        animation_context_amount.start_complete(group0, 1.0, loop_mode, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        let mut events = vec![];
+        for delta in [100, 200, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            events.push(range_events(&animation_context_amount, group0));
+        }
+        assert_eq!(events, vec![vec![], vec![], vec![(true, 1)]]);
+        assert_eq!(animation_context_amount.active_range_events(group0).unwrap(), &vec![1]);
+
+        animation_context_amount.seek(group0, 0.7).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(range_events(&animation_context_amount, group0), vec![(false, 1), (true, 2)]);
+        assert_eq!(animation_context_amount.active_range_events(group0).unwrap(), &vec![2]);
+
+        animation_context_amount.stop(group0).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(range_events(&animation_context_amount, group0), vec![(false, 2)]);
+        assert!(animation_context_amount.active_range_events(group0).unwrap().is_empty());
+
+        // 一次计算跨越整个区间, 正向 与 反向
+        for (group, loop_mode, expect) in [
+            (groups[1].0, groups[1].1, vec![(true, 1), (false, 1), (true, 2), (false, 2)]),
+            (groups[2].0, groups[2].1, vec![(true, 2), (false, 2), (true, 1), (false, 1)]),
+        ] {
+            animation_context_amount.start_complete(group, 1.0, loop_mode, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+            let mut events = vec![];
+            for delta in [100, 10000, 100] {
+                type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+                events.append(&mut range_events(&animation_context_amount, group));
+            }
+            assert_eq!(events, expect);
+        }
+
+        // 删除动画组 同样退出活动区间
+        animation_context_amount.start_complete(group0, 1.0, loop_mode, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        animation_context_amount.seek(group0, 0.7).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(animation_context_amount.active_range_events(group0).unwrap(), &vec![2]);
+        animation_context_amount.del_animation_group(group0);
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(range_events(&animation_context_amount, group0), vec![(false, 2)]);
+    }
+
+    #[test]
+    fn test_event_dispatcher() {
+        use std::{rc::Rc, cell::RefCell};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        let mut groups = vec![];
+        for _ in 0..2 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            groups.push(group);
+        }
+        let (group0, group1) = (groups[0], groups[1]);
+        animation_context_amount.add_frame_event(group0, 15., frame_count, 7).unwrap();
+        animation_context_amount.debug(true);
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut handles = vec![];
+        for (name, group) in [("g0", group0), ("g1", group1)] {
+            let mut listener = AnimationListener::new(group);
+            let temp = log.clone();
+            listener.on_start.push(Box::new(move || { temp.borrow_mut().push(format!("{} start", name)); Ok(EAnimationEventResult::None) }));
+            let temp = log.clone();
+            listener.on_loop.push(Box::new(move |count| { temp.borrow_mut().push(format!("{} loop {}", name, count)); Ok(EAnimationEventResult::None) }));
+            let temp = log.clone();
+            listener.on_frame_event.push(Box::new(move |datas| { temp.borrow_mut().push(format!("{} frame {:?}", name, datas)); Ok(EAnimationEventResult::None) }));
+            let temp = log.clone();
+            listener.on_pause.push(Box::new(move || { temp.borrow_mut().push(format!("{} pause", name)); Ok(EAnimationEventResult::None) }));
+            let temp = log.clone();
+            listener.on_stop.push(Box::new(move || { temp.borrow_mut().push(format!("{} stop", name)); Ok(EAnimationEventResult::None) }));
+            handles.push(animation_context_amount.add_listener(listener));
+        }
+        // 全局监听 只响应一次
+        let mut listener = AnimationListener::global();
+        let temp = log.clone();
+        listener.on_start.push(Box::new(move || { temp.borrow_mut().push(String::from("all start")); Ok(EAnimationEventResult::RemoveListen) }));
+        animation_context_amount.add_listener(listener);
+
+        let mut tick = |ctx: &mut AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, delta: u64| {
+            type_animation_ctx_mgr.anime(ctx, delta).unwrap();
+            ctx.dispatch().unwrap();
+            replace(&mut *log.borrow_mut(), vec![])
+        };
+
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(2)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        assert_eq!(tick(&mut animation_context_amount, 100), vec!["g0 start", "all start"]);
+        assert!(tick(&mut animation_context_amount, 1000).is_empty());
+        assert_eq!(tick(&mut animation_context_amount, 100), vec!["g0 frame [7]", "g0 loop 1"]);
+
+        animation_context_amount.pause(group0).unwrap();
+        assert_eq!(tick(&mut animation_context_amount, 100), vec!["g0 pause"]);
+
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        assert_eq!(tick(&mut animation_context_amount, 100), vec!["g1 start"]);
+        animation_context_amount.stop(group1).unwrap();
+        assert_eq!(tick(&mut animation_context_amount, 100), vec!["g1 stop"]);
+
+        // 按句柄移除, 动画组删除时其监听器一起移除
+        assert!(animation_context_amount.remove_listener(handles[0]));
+        animation_context_amount.del_animation_group(group1);
+        assert!(!animation_context_amount.remove_listener(handles[1]));
+    }
+
+    #[test]
+    fn test_listener_lifecycle_hooks() {
+        use std::{rc::Rc, cell::RefCell};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+        animation_context_amount.add_range_event(group, 6., 12., frame_count, 3).unwrap();
+        animation_context_amount.debug(true);
+
+        // 区间 延时 循环开始 中断 删除 事件 同样派发给 AnimationListener
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut listener = AnimationListener::global();
+        let temp = log.clone();
+        listener.on_delay_end.push(Box::new(move || { temp.borrow_mut().push(String::from("delay end")); Ok(EAnimationEventResult::None) }));
+        let temp = log.clone();
+        listener.on_iteration_start.push(Box::new(move |index| { temp.borrow_mut().push(format!("iteration {}", index)); Ok(EAnimationEventResult::None) }));
+        let temp = log.clone();
+        listener.on_range_enter.push(Box::new(move |data| { temp.borrow_mut().push(format!("enter {}", data)); Ok(EAnimationEventResult::None) }));
+        let temp = log.clone();
+        listener.on_range_exit.push(Box::new(move |data| { temp.borrow_mut().push(format!("exit {}", data)); Ok(EAnimationEventResult::None) }));
+        let temp = log.clone();
+        listener.on_cancel.push(Box::new(move |reason| { temp.borrow_mut().push(format!("cancel {:?}", reason)); Ok(EAnimationEventResult::None) }));
+        let temp = log.clone();
+        listener.on_remove.push(Box::new(move || { temp.borrow_mut().push(String::from("remove")); Ok(EAnimationEventResult::None) }));
+        animation_context_amount.add_listener(listener);
+
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 100., EFillMode::NONE).unwrap();
+        for _ in 0..14 {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+            animation_context_amount.dispatch().unwrap();
+        }
+        animation_context_amount.del_animation_group(group);
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        animation_context_amount.dispatch().unwrap();
+
+        assert_eq!(*log.borrow(), vec!["delay end", "iteration 0", "enter 3", "exit 3", "iteration 1", "enter 3", "exit 3", "cancel Removed", "remove"]);
+    }
+
+    #[test]
+    fn test_event_payload_looped_count() {
+        use std::{rc::Rc, cell::RefCell};
+        use pi_animation::{animation_listener::EAnimationEvent, base::ETimeStepMode};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+        animation_context_amount.add_frame_event(group, 15., frame_count, 5).unwrap();
+
+        let log = Rc::new(RefCell::new(vec![]));
+        let temp = log.clone();
+        animation_context_amount.add_event_handler(group, Box::new(move |payload, _| {
+            if payload.event == EAnimationEvent::FrameEvent || payload.event == EAnimationEvent::Loop {
+                temp.borrow_mut().push((payload.event, payload.looped_count));
+            }
+            Ok(EAnimationEventResult::None)
+        }));
+
+        // 一次计算跨过多次循环 - 每个事件携带发生时的循环次数
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        animation_context_amount.dispatch().unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 2000).unwrap();
+        animation_context_amount.dispatch().unwrap();
+        assert_eq!(*log.borrow(), vec![
+            (EAnimationEvent::FrameEvent, 0), (EAnimationEvent::Loop, 1),
+            (EAnimationEvent::FrameEvent, 1), (EAnimationEvent::Loop, 2),
+        ]);
+    }
+
+    #[test]
+    fn test_event_handler_commands() {
+        use std::sync::mpsc::channel;
+        use pi_animation::animation_listener::EAnimationEvent;
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+
+        let mut groups = vec![];
+        for _ in 0..2 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            groups.push(group);
+        }
+        let (group0, group1) = (groups[0], groups[1]);
+        animation_context_amount.debug(true);
+
+        let (sender, receiver) = channel();
+        // 回调持有可变状态, 动画组0 结束时 启动动画组1
+        let temp = sender.clone();
+        let mut count = 0;
+        animation_context_amount.add_event_handler(group0, Box::new(move |payload, commands| {
+            count += 1;
+            temp.send((count, payload.group, payload.event, payload.running_time_ms)).unwrap();
+            if payload.event == EAnimationEvent::End {
+                commands.start_complete(group1, 1.0, pi_animation::animation_command::AnimationStartParam::new(ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE));
+            }
+            Ok(EAnimationEventResult::None)
+        }));
+        let temp = sender.clone();
+        animation_context_amount.add_event_handler(DefaultKey::null(), Box::new(move |payload, _| {
+            if payload.group == group1 {
+                temp.send((0, payload.group, payload.event, payload.running_time_ms)).unwrap();
+                Ok(EAnimationEventResult::RemoveListen)
+            } else {
+                Ok(EAnimationEventResult::None)
+            }
+        }));
+
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        for delta in [100, 2000, 100, 100, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            animation_context_amount.dispatch().unwrap();
+        }
+        let received: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(received, vec![
+            (1, group0, EAnimationEvent::Start, 0.),
+            (2, group0, EAnimationEvent::IterationStart, 0.),
+            (3, group0, EAnimationEvent::End, 2100.),
+            (0, group1, EAnimationEvent::Start, 0.),
+        ]);
+    }
+
+    #[test]
+    fn test_listener_error_policy() {
+        use std::{rc::Rc, cell::Cell};
+        use pi_animation::{animation_listener::{EAnimationEvent, EListenerErrorPolicy}, error::EAnimationError};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        animation_context_amount.debug(true);
+
+        let mut counters = vec![];
+        for policy in [EListenerErrorPolicy::Keep, EListenerErrorPolicy::Remove, EListenerErrorPolicy::Propagate] {
+            let counter = Rc::new(Cell::new(0));
+            let temp = counter.clone();
+            let mut listener = AnimationListener::new(group0);
+            listener.on_pause.push(Box::new(move || {
+                temp.set(temp.get() + 1);
+                Err(EAnimationError::NotFoundAttr)
+            }));
+            let id = animation_context_amount.add_listener(listener);
+            assert!(animation_context_amount.set_listener_error_policy(id, policy));
+            counters.push(counter);
+        }
+        // 执行失败的操作 同样返回
+        animation_context_amount.add_event_handler(group0, Box::new(move |payload, commands| {
+            if payload.event == EAnimationEvent::Pause {
+                commands.pause(payload.group);
+            }
+            Ok(EAnimationEventResult::None)
+        }));
+
+        for round in 1..3 {
+            animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+            animation_context_amount.dispatch().unwrap();
+            animation_context_amount.pause(group0).unwrap();
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+            assert_eq!(animation_context_amount.dispatch(), Err(vec![
+                (group0, EAnimationEvent::Pause, EAnimationError::NotFoundAttr),
+                (group0, EAnimationEvent::None, EAnimationError::AnimationGroupNotPlaying),
+            ]));
+            let calls: Vec<_> = counters.iter().map(|v| v.get()).collect();
+            assert_eq!(calls, vec![round, 1, round]);
+        }
+
+        // 旧的 animation_event 接口 移除出错的回调, 错误与动画组和事件类型一起返回
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V1 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target.anime_target_id()).unwrap();
+        let mut listener = AnimationListener::<u32>::new(group1);
+        listener.on_start.push(Box::new(|| Err(EAnimationError::NotFoundAttr)));
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(animation_context_amount.animation_event(&mut listener, None), Err(vec![
+            (group1, EAnimationEvent::Start, EAnimationError::NotFoundAttr),
+        ]));
+        assert!(listener.on_start.is_empty());
+    }
+
+    #[test]
+    fn test_event_stream() {
+        use std::sync::mpsc::channel;
+        use pi_animation::animation_listener::AnimationEvent;
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        animation_context_amount.add_frame_event(group0, 15., frame_count, 5).unwrap();
+        animation_context_amount.debug(true);
+
+        // 其他线程接收事件
+        let (sender, receiver) = channel();
+        animation_context_amount.set_event_sender(Some(sender));
+        let handle = std::thread::spawn(move || receiver.iter().collect::<Vec<AnimationEvent>>());
+
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        let mut pulled = vec![];
+        for delta in [100, 2000, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            pulled.extend(animation_context_amount.drain_events());
+            assert!(animation_context_amount.events().is_empty());
+        }
+        let expect = vec![AnimationEvent::Start(group0), AnimationEvent::IterationStart(group0, 0), AnimationEvent::FrameEvent(group0, 5), AnimationEvent::End(group0)];
+        assert_eq!(pulled, expect);
+
+        animation_context_amount.set_event_sender(None);
+        assert_eq!(handle.join().unwrap(), expect);
+    }
+
+    #[test]
+    fn test_animation_future() {
+        use std::{future::Future, pin::Pin, sync::{Arc as SyncArc, atomic::{AtomicUsize, Ordering}}, task::{Context, Poll, Wake, Waker}};
+        use pi_animation::animation_future::EAnimationEndReason;
+
+        // 最简执行器 - 记录唤醒次数
+        struct CountWaker(AtomicUsize);
+        impl Wake for CountWaker {
+            fn wake(self: SyncArc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+        let counter = SyncArc::new(CountWaker(AtomicUsize::new(0)));
+        let waker = Waker::from(counter.clone());
+        fn poll<F: Future + ?Sized>(future: &mut Pin<Box<F>>, waker: &Waker) -> Poll<F::Output> {
+            future.as_mut().poll(&mut Context::from_waker(waker))
+        }
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let mut groups = vec![];
+        for _ in 0..3 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            groups.push(group);
+        }
+        let (group0, group1, group2) = (groups[0], groups[1], groups[2]);
+        animation_context_amount.add_frame_event(group0, 15., frame_count, 9).unwrap();
+        animation_context_amount.debug(true);
+
+        // 脚本化的等待序列
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(2)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        let frame_wait = animation_context_amount.frame_event_reached(group0, 9).unwrap();
+        let loop_wait = animation_context_amount.looped(group0, 1).unwrap();
+        let finished = animation_context_amount.finished(group0).unwrap();
+        let mut script = Box::pin(async move {
+            let frame = frame_wait.await;
+            let looped = loop_wait.await;
+            let reason = finished.await;
+            (frame, looped, reason)
+        });
+        let mut never_loop = Box::pin(animation_context_amount.looped(group0, 5).unwrap());
+
+        assert!(poll(&mut script, &waker).is_pending());
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 500).unwrap();
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(counter.0.load(Ordering::SeqCst), 1);
+        assert!(poll(&mut script, &waker).is_pending());
+        assert!(poll(&mut never_loop, &waker).is_pending());
+        for _ in 0..3 {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        }
+        assert_eq!(poll(&mut script, &waker), Poll::Ready((Ok(()), Ok(()), EAnimationEndReason::Completed)));
+        assert_eq!(poll(&mut never_loop, &waker), Poll::Ready(Err(EAnimationEndReason::Completed)));
+        // 已结束的动画组 立即完成
+        assert_eq!(poll(&mut Box::pin(animation_context_amount.finished(group0).unwrap()), &waker), Poll::Ready(EAnimationEndReason::Completed));
+        assert_eq!(poll(&mut Box::pin(animation_context_amount.looped(group0, 1).unwrap()), &waker), Poll::Ready(Ok(())));
+        assert_eq!(poll(&mut Box::pin(animation_context_amount.looped(group0, 5).unwrap()), &waker), Poll::Ready(Err(EAnimationEndReason::Completed)));
+
+        // 停止 与 删除
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        animation_context_amount.start_complete(group2, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        let mut stopped = Box::pin(animation_context_amount.finished(group1).unwrap());
+        let mut removed = Box::pin(animation_context_amount.finished(group2).unwrap());
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        animation_context_amount.stop(group1).unwrap();
+        assert!(poll(&mut stopped, &waker).is_pending());
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(poll(&mut stopped, &waker), Poll::Ready(EAnimationEndReason::Stopped));
+        assert_eq!(poll(&mut Box::pin(animation_context_amount.finished(group1).unwrap()), &waker), Poll::Ready(EAnimationEndReason::Stopped));
+        assert_eq!(poll(&mut Box::pin(animation_context_amount.frame_event_reached(group1, 9).unwrap()), &waker), Poll::Ready(Err(EAnimationEndReason::Stopped)));
+        animation_context_amount.del_animation_group(group2);
+        assert_eq!(poll(&mut removed, &waker), Poll::Ready(EAnimationEndReason::Removed));
+        assert!(animation_context_amount.finished(group2).is_err());
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        use pi_animation::animation_listener::{AnimationEvent, EAnimationEndReason};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let mut groups = vec![];
+        for _ in 0..3 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            groups.push(group);
+        }
+        let (group0, group1, group2) = (groups[0], groups[1], groups[2]);
+        animation_context_amount.debug(true);
+
+        // 延时结束 与 每次循环开始
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 200., EFillMode::NONE).unwrap();
+        let mut events = vec![];
+        for delta in [100, 100, 100, 1000, 100] {
+            type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+            events.extend(animation_context_amount.drain_events());
+        }
+        assert_eq!(events, vec![
+            AnimationEvent::DelayEnd(group0), AnimationEvent::Start(group0), AnimationEvent::IterationStart(group0, 0),
+            AnimationEvent::Loop(group0, 1), AnimationEvent::IterationStart(group0, 1),
+        ]);
+
+        // 暂停 与 停止 携带当时的播放位置
+        let position = animation_context_amount.group_infos.get(group0).unwrap().position();
+        assert_eq!(position.looped_count, 1);
+        animation_context_amount.pause(group0).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 200., EFillMode::NONE).unwrap();
+        animation_context_amount.stop(group0).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(animation_context_amount.drain_events().collect::<Vec<_>>(), vec![
+            AnimationEvent::Pause(group0, position),
+            AnimationEvent::Cancel(group0, EAnimationEndReason::Stopped, position),
+            AnimationEvent::Stop(group0, position),
+        ]);
+
+        // 删除 - 播放中的动画组 先触发 Cancel
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        let position = animation_context_amount.group_infos.get(group1).unwrap().position();
+        animation_context_amount.drain_events();
+        animation_context_amount.del_animation_group(group1);
+        animation_context_amount.del_animation_group(group2);
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert_eq!(events, vec![
+            AnimationEvent::Cancel(group1, EAnimationEndReason::Removed, position),
+            AnimationEvent::Remove(group1, position),
+            AnimationEvent::Remove(group2, Default::default()),
+        ]);
+        assert_eq!(events[0].position().unwrap().running_time_ms, 100.);
+    }
+
+    #[test]
+    fn test_loop_catch_up() {
+        use pi_animation::{animation_listener::AnimationEvent, curve_frame_event::EFrameEventCatchUp};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let mut groups = vec![];
+        for _ in 0..4 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            animation_context_amount.add_frame_event(group, 15., frame_count, 9).unwrap();
+            groups.push(group);
+        }
+        animation_context_amount.set_frame_event_catch_up(groups[1], EFrameEventCatchUp::Once).unwrap();
+        animation_context_amount.set_frame_event_catch_up(groups[2], EFrameEventCatchUp::Skip).unwrap();
+        animation_context_amount.debug(true);
+
+        for group in groups[0..3].iter() {
+            animation_context_amount.start_complete(*group, 1.0, ELoopMode::Positive(Some(5)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        }
+        // 有限循环 一次越过结束点
+        animation_context_amount.start_complete(groups[3], 1.0, ELoopMode::Positive(Some(2)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 3400).unwrap();
+        animation_context_amount.drain_events();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        let events: Vec<_> = animation_context_amount.drain_events().collect();
+
+        let group_events = |group: DefaultKey| -> Vec<AnimationEvent> {
+            events.iter().filter(|e| e.group() == group).cloned().collect()
+        };
+        let frame_events = |group: DefaultKey| -> usize {
+            group_events(group).iter().filter(|e| matches!(e, AnimationEvent::FrameEvent(_, 9))).count()
+        };
+        // 跨过 3 次循环
+        assert_eq!(animation_context_amount.group_infos.get(groups[0]).unwrap().loop_crossed, 3);
+        let loops: Vec<_> = group_events(groups[0]).iter().filter_map(|e| match e {
+            AnimationEvent::Loop(_, count) => Some(*count),
+            _ => None,
+        }).collect();
+        assert_eq!(loops, vec![1, 2, 3]);
+        assert_eq!(frame_events(groups[0]), 4);
+        // 帧事件 与 循环事件 按时间先后交错
+        let (group0, frame) = (groups[0], AnimationEvent::FrameEvent(groups[0], 9));
+        assert_eq!(group_events(group0), vec![
+            frame, AnimationEvent::Loop(group0, 1), AnimationEvent::IterationStart(group0, 1),
+            frame, AnimationEvent::Loop(group0, 2), AnimationEvent::IterationStart(group0, 2),
+            frame, AnimationEvent::Loop(group0, 3), AnimationEvent::IterationStart(group0, 3),
+            frame,
+        ]);
+        assert_eq!(frame_events(groups[1]), 1);
+        assert_eq!(frame_events(groups[2]), 2);
+
+        // 结束前 经过的循环也会报告
+        let end_events: Vec<_> = group_events(groups[3]).into_iter().filter(|e| !matches!(e, AnimationEvent::FrameEvent(_, _))).collect();
+        assert_eq!(end_events, vec![AnimationEvent::Loop(groups[3], 1), AnimationEvent::IterationStart(groups[3], 1), AnimationEvent::End(groups[3])]);
+    }
+
+    #[test]
+    fn test_time_step_mode() {
+        use pi_animation::{animation_listener::AnimationEvent, base::ETimeStepMode};
+
+        // 相同的总时间 以不同的切分方式推进
+        let run = |mode: ETimeStepMode, deltas: &[u64]| -> (f32, u32, Vec<AnimationEvent>) {
+            let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+            let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+            animation_context_amount.time_step_mode(mode);
+
+            let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+            let frame_count = 30 as FrameIndex;
+            let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 250., EFillMode::NONE).unwrap();
+
+            let mut events = vec![];
+            for delta in deltas {
+                type_animation_ctx_mgr.anime(&mut animation_context_amount, *delta).unwrap();
+                events.extend(animation_context_amount.drain_events());
+            }
+            let info = animation_context_amount.group_infos.get(group).unwrap();
+            (info.amount_in_second, info.looped_count, events)
+        };
+
+        let split = [100; 15];
+        let (amount, looped_count, events) = run(ETimeStepMode::Continuous, &[700, 800]);
+        assert_eq!(run(ETimeStepMode::Continuous, &split), (amount, looped_count, events.clone()));
+        assert!((amount - 0.25).abs() < 0.0001);
+        assert_eq!(looped_count, 1);
+        assert_eq!(events.iter().filter(|e| matches!(e, AnimationEvent::Loop(_, 1))).count(), 1);
+
+        // 固定步长 余量留到下次计算
+        let (amount, looped_count, events) = run(ETimeStepMode::Fixed(16.), &[700, 800]);
+        assert_eq!(run(ETimeStepMode::Fixed(16.), &split), (amount, looped_count, events));
+        assert!((amount - 0.248).abs() < 0.0001);
+
+        // 一次计算跨过多个步长 与逐步推进相同, 包括循环 和 结束
+        let (amount, looped_count, events) = run(ETimeStepMode::Fixed(16.), &[3600]);
+        assert_eq!(run(ETimeStepMode::Fixed(16.), &[100; 36]), (amount, looped_count, events.clone()));
+        assert_eq!(events.iter().filter(|e| matches!(e, AnimationEvent::Loop(_, _))).count(), 2);
+        assert_eq!(events.iter().filter(|e| matches!(e, AnimationEvent::End(_))).count(), 1);
+    }
+
+    #[test]
+    fn test_absolute_clock() {
+        use pi_animation::animation_listener::AnimationEvent;
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let mut groups = vec![];
+        for _ in 0..2 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+            groups.push(group);
+        }
+        let (group0, group1) = (groups[0], groups[1]);
+        let state = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, group: DefaultKey| {
+            let info = ctx.group_infos.get(group).unwrap();
+            (info.looped_count, info.amount_in_second)
+        };
+
+        // 首次计算的时刻 作为启动时刻
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 10.0).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 10.5).unwrap();
+        assert_eq!(state(&animation_context_amount, group0), (0, 0.5));
+        assert_eq!(state(&animation_context_amount, group1), (0, 0.5));
+
+        // 暂停期间的时间不计入
+        animation_context_amount.pause(group1).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 12.0).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 12.25).unwrap();
+        assert_eq!(state(&animation_context_amount, group0), (2, 0.25));
+        assert_eq!(state(&animation_context_amount, group1), (0, 0.75));
+
+        // 非整数毫秒的帧间隔 不累计误差
+        for k in 1..=120 {
+            type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 12.25 + k as f64 / 60.0).unwrap();
+        }
+        let (looped_count, amount_in_second) = state(&animation_context_amount, group0);
+        assert_eq!(looped_count, 4);
+        assert!((amount_in_second - 0.25).abs() < 0.0001);
+
+        // 回到任意时刻
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 10.5).unwrap();
+        assert_eq!(state(&animation_context_amount, group0), (0, 0.5));
+        assert_eq!(animation_context_amount.clock_ms(), Some(10500.));
+
+        // 已播放完成的动画组 回退到结束之前 重新激活
+        let group2 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group2, target.anime_target_id()).unwrap();
+        animation_context_amount.start_complete(group2, 1.0, ELoopMode::Positive(Some(2)), 60, AnimationAmountCalc::default(), 0., EFillMode::FORWARDS).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 11.0).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 14.0).unwrap();
+        assert!(animation_context_amount.drain_events().any(|e| e == AnimationEvent::End(group2)));
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 15.0).unwrap();
+        assert!(!animation_context_amount.drain_events().any(|e| e.group() == group2));
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 12.0).unwrap();
+        assert_eq!(state(&animation_context_amount, group2), (1, 0.5));
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 13.5).unwrap();
+        let events: Vec<_> = animation_context_amount.drain_events().filter(|e| e.group() == group2).collect();
+        assert_eq!(events.iter().filter(|e| **e == AnimationEvent::End(group2)).count(), 1);
+        assert!(!events.iter().any(|e| *e == AnimationEvent::Start(group2)));
+    }
+
+    #[test]
+    fn test_fractional_time() {
+        use std::time::Duration;
+        use pi_animation::base::ETimeStepMode;
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+
+        // 运行 10 小时后 小数毫秒的间隔仍然有效
+        animation_context_amount.anime_curve_calc_ms(36_000_000.25, &mut type_animation_ctx_mgr.runtime_infos);
+        for _ in 0..10 {
+            animation_context_amount.anime_curve_calc_duration(Duration::from_micros(16_500), &mut type_animation_ctx_mgr.runtime_infos);
+        }
+        let info = animation_context_amount.group_infos.get(group).unwrap();
+        assert_eq!(info.looped_count, 36_000);
+        assert!((info.running_time_ms - 36_000_165.25).abs() < 0.0001);
+        assert!((info.amount_in_second - 0.16525).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_large_delta_policy() {
+        use std::{rc::Rc, cell::Cell};
+        use pi_animation::{animation_listener::AnimationEvent, base::{ETimeStepMode, ELargeDeltaPolicy, MAX_CATCH_UP_STEPS}};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+        animation_context_amount.set_large_delta_policy(ELargeDeltaPolicy::Clamp(100.));
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let mut groups = vec![];
+        for _ in 0..4 {
+            let group = animation_context_amount.create_animation_group();
+            let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+            animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+            animation_context_amount.add_frame_event(group, 15., frame_count, 3).unwrap();
+            animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+            groups.push(group);
+        }
+        // 动画组单独设置 覆盖 context 的设置
+        animation_context_amount.set_group_large_delta_policy(groups[1], Some(ELargeDeltaPolicy::CatchUp(250.))).unwrap();
+        animation_context_amount.set_group_large_delta_policy(groups[2], Some(ELargeDeltaPolicy::Skip(250.))).unwrap();
+        animation_context_amount.set_group_large_delta_policy(groups[3], Some(ELargeDeltaPolicy::None)).unwrap();
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        animation_context_amount.drain_events();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 2000).unwrap();
+        let events: Vec<_> = animation_context_amount.drain_events().collect();
+        let group_events = |group: DefaultKey| -> Vec<AnimationEvent> {
+            events.iter().filter(|e| e.group() == group).cloned().collect()
+        };
+        let running_time = |group: DefaultKey| animation_context_amount.group_infos.get(group).unwrap().running_time_ms;
+
+        // 限制间隔
+        assert_eq!(running_time(groups[0]), 200.);
+        assert!(group_events(groups[0]).is_empty());
+        // 分步追赶 产生全部事件
+        let all = |group: DefaultKey| vec![
+            AnimationEvent::FrameEvent(group, 3),
+            AnimationEvent::Loop(group, 1), AnimationEvent::IterationStart(group, 1),
+            AnimationEvent::FrameEvent(group, 3),
+            AnimationEvent::Loop(group, 2), AnimationEvent::IterationStart(group, 2),
+        ];
+        assert_eq!(running_time(groups[1]), 2100.);
+        assert_eq!(group_events(groups[1]), all(groups[1]));
+        // 跳过 不产生事件
+        assert_eq!(running_time(groups[2]), 2100.);
+        assert!(group_events(groups[2]).is_empty());
+        // 不处理
+        assert_eq!(running_time(groups[3]), 2100.);
+        // 不处理 - 事件按经过的时间先后排列
+        assert_eq!(group_events(groups[3]), all(groups[3]));
+
+        // 分步追赶 各步的事件标记 和 跨过的循环 累计到本次计算
+        let info = animation_context_amount.group_infos.get(groups[1]).unwrap();
+        assert_eq!((info.last_running_time_ms, info.loop_crossed, info.start_event), (100., 2, false));
+
+        // 跳过时 通过 animation_event 查询 同样不报告 循环 和 帧事件
+        let mut curve_frame_event = CurveFrameEvent::<u32>::new(frame_count as f32);
+        curve_frame_event.add(15, 3);
+        let listen = |group: DefaultKey| {
+            let count = Rc::new(Cell::new(0));
+            let mut listener = AnimationListener::<u32>::new(group);
+            let temp = count.clone();
+            listener.on_loop.push(Box::new(move |_| { temp.set(temp.get() + 1); Ok(EAnimationEventResult::None) }));
+            let temp = count.clone();
+            listener.on_frame_event.push(Box::new(move |datas| { temp.set(temp.get() + datas.len()); Ok(EAnimationEventResult::None) }));
+            animation_context_amount.animation_event(&mut listener, Some(&curve_frame_event)).unwrap();
+            count.get()
+        };
+        assert_eq!(listen(groups[2]), 0);
+        assert!(listen(groups[3]) > 0);
+
+        // 分步追赶 步数超过上限时 按跳过处理
+        let delta = 250 * MAX_CATCH_UP_STEPS as u64 + 1;
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, delta).unwrap();
+        assert!(!animation_context_amount.drain_events().any(|e| e.group() == groups[1]));
+        assert_eq!(animation_context_amount.group_infos.get(groups[1]).unwrap().running_time_ms, 2100. + delta as f64);
+    }
+
+    #[test]
+    fn test_time_channel() {
+        use pi_animation::{base::ETimeStepMode, error::EAnimationError};
+
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let create = |type_animation_ctx_mgr: &mut TypeAnimationContextMgr, count: usize| {
+            let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+            animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+            let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+            let mut groups = vec![];
+            for _ in 0..count {
+                let group = animation_context_amount.create_animation_group();
+                let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+                animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+                animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+                groups.push(group);
+            }
+            (animation_context_amount, groups)
+        };
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let (mut animation_context_amount, groups) = create(&mut type_animation_ctx_mgr, 4);
+        let gameplay = animation_context_amount.create_time_channel("gameplay", None).unwrap();
+        let enemy = animation_context_amount.create_time_channel("enemy", Some(gameplay)).unwrap();
+        let ui = animation_context_amount.create_time_channel("ui", None).unwrap();
+        assert_eq!(animation_context_amount.create_time_channel("ui", None), Err(EAnimationError::TimeChannelNameExists));
+        assert_eq!(animation_context_amount.time_channel("enemy"), Some(enemy));
+        animation_context_amount.set_group_time_channel(groups[0], Some(gameplay)).unwrap();
+        animation_context_amount.set_group_time_channel(groups[1], Some(enemy)).unwrap();
+        animation_context_amount.set_group_time_channel(groups[2], Some(ui)).unwrap();
+
+        // 子弹时间 - 嵌套通道的缩放相乘, ui 不受影响
+        animation_context_amount.set_time_channel_scale(gameplay, 0.5).unwrap();
+        animation_context_amount.set_time_channel_scale(enemy, 0.5).unwrap();
+        let running_times = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>| -> Vec<f64> {
+            groups.iter().map(|group| ctx.group_infos.get(*group).unwrap().running_time_ms).collect()
+        };
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 400).unwrap();
+        assert_eq!(running_times(&animation_context_amount), vec![250., 125., 500., 500.]);
+
+        // 暂停父通道 子通道一起暂停
+        animation_context_amount.set_time_channel_paused(gameplay, true).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(running_times(&animation_context_amount), vec![250., 125., 600., 600.]);
+
+        // 移除通道 动画组与子通道 转到父通道
+        animation_context_amount.remove_time_channel(gameplay).unwrap();
+        assert_eq!(animation_context_amount.time_channels().get(enemy).unwrap().parent(), None);
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(running_times(&animation_context_amount), vec![350., 175., 700., 700.]);
+
+        // 绝对时钟驱动时 通道有各自的时钟
+        let (mut animation_context_amount, groups) = create(&mut type_animation_ctx_mgr, 2);
+        let slow = animation_context_amount.create_time_channel("slow", None).unwrap();
+        animation_context_amount.set_time_channel_scale(slow, 0.5).unwrap();
+        animation_context_amount.set_group_time_channel(groups[0], Some(slow)).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 1.0).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 2.0).unwrap();
+        assert_eq!(animation_context_amount.group_infos.get(groups[0]).unwrap().running_time_ms, 500.);
+        assert_eq!(animation_context_amount.group_infos.get(groups[1]).unwrap().running_time_ms, 1000.);
+
+        // 切换通道 保持当前进度, 之后按新通道的时钟推进
+        animation_context_amount.set_group_time_channel(groups[0], None).unwrap();
+        animation_context_amount.set_group_time_channel(groups[1], Some(slow)).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 3.0).unwrap();
+        assert_eq!(animation_context_amount.group_infos.get(groups[0]).unwrap().running_time_ms, 1500.);
+        assert_eq!(animation_context_amount.group_infos.get(groups[1]).unwrap().running_time_ms, 1500.);
+    }
+
+    #[test]
+    fn test_speed_curve() {
+        use pi_animation::{base::ETimeStepMode, speed_curve::SpeedCurve, animation_listener::AnimationEvent};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+
+        // 速度跨过 0 的一段 只计算速度为正的部分
+        let curve_cross = SpeedCurve::new(vec![(0., 1.), (1000., -1.)]);
+        assert_eq!(curve_cross.keys(), &[(0., 1.), (500., 0.), (1000., -1.)]);
+        assert_eq!(curve_cross.integral(1000.), 250.);
+        assert_eq!(curve_cross.inverse(125.), Some(500. - 250. * 2f64.sqrt()));
+        assert_eq!(curve_cross.inverse(300.), None);
+
+        // 前 500ms 速度从 0 加速到 1
+        animation_context_amount.set_group_speed_curve(group, Some(SpeedCurve::ramp(0., 1., 500.))).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(None));
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(Some(2)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(Some(2250.)));
+
+        let state = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>| {
+            let info = ctx.group_infos.get(group).unwrap();
+            (info.running_time_ms, info.looped_count)
+        };
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 250).unwrap();
+        assert_eq!(state(&animation_context_amount), (62.5, 0));
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(Some(2000.)));
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 750).unwrap();
+        assert_eq!(state(&animation_context_amount), (750., 0));
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        assert_eq!(state(&animation_context_amount), (1750., 1));
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(Some(250.)));
+
+        // 运行中移除速度曲线 保持当前进度
+        animation_context_amount.set_group_speed_curve(group, None).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(Some(250.)));
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 250).unwrap();
+        assert!(animation_context_amount.events().contains(&AnimationEvent::End(group)));
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(None));
+    }
+
+    #[test]
+    fn test_css_delay() {
+        use pi_animation::{base::ETimeStepMode, animation_listener::AnimationEvent};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V1 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target.anime_target_id()).unwrap();
+
+        // 负延时 从播放途中开始
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), -500., EFillMode::NONE).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group0), Ok(Some(500.)));
+        // 结束延时 保持最终状态
+        animation_context_amount.set_group_end_delay(group1, 300.).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Not, 60, AnimationAmountCalc::default(), 0., EFillMode::FORWARDS).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group1), Ok(Some(1300.)));
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert!(events.contains(&AnimationEvent::Start(group0)));
+        assert!(!events.contains(&AnimationEvent::DelayEnd(group0)));
+        assert_eq!(animation_context_amount.group_infos.get(group0).unwrap().running_time_ms, 600.);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 900).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert!(events.contains(&AnimationEvent::End(group0)));
+        assert!(!events.contains(&AnimationEvent::End(group1)));
+        assert_eq!(animation_context_amount.group_infos.get(group1).unwrap().amount_in_second, 1.);
+        assert_eq!(animation_context_amount.remaining_time_ms(group1), Ok(Some(300.)));
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 200).unwrap();
+        assert!(!animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group1)));
+        assert_eq!(animation_context_amount.group_infos.get(group1).unwrap().amount_in_second, 1.);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert!(animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group1)));
+        assert_eq!(animation_context_amount.remaining_time_ms(group1), Ok(None));
+    }
+
+    #[test]
+    fn test_iteration_delay() {
+        use pi_animation::{base::{ETimeStepMode, IterationDelay, EIterationHold, SeededRandom}, animation_listener::AnimationEvent};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V1 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target.anime_target_id()).unwrap();
+
+        animation_context_amount.set_group_iteration_delay(group0, Some(IterationDelay::new(500., EIterationHold::LastFrame))).unwrap();
+        animation_context_amount.set_group_iteration_delay(group1, Some(IterationDelay::new(500., EIterationHold::NextFirstFrame).jitter(200., 7))).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group0), Ok(Some(4000.)));
+
+        let state = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, group| {
+            let info = ctx.group_infos.get(group).unwrap();
+            (info.amount_in_second, info.looped_count)
+        };
+
+        // 到达循环边界 进入停顿, 不触发循环事件
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        assert!(!animation_context_amount.drain_events().any(|event| event == AnimationEvent::Loop(group0, 1)));
+        assert_eq!(state(&animation_context_amount, group0), (1., 0));
+        assert_eq!(state(&animation_context_amount, group1), (0., 0));
+        assert_eq!(animation_context_amount.remaining_time_ms(group0), Ok(Some(3000.)));
+        // 停顿时长 由种子确定
+        let pause_ms = 500. + 200. * SeededRandom::new(7).next_f64();
+        let remaining = animation_context_amount.remaining_time_ms(group1).unwrap().unwrap();
+        assert!((remaining - (2000. + pause_ms + 500.)).abs() < 0.001);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 300).unwrap();
+        assert!(!animation_context_amount.drain_events().any(|event| event == AnimationEvent::Loop(group0, 1)));
+        assert_eq!(state(&animation_context_amount, group0), (1., 0));
+
+        // 停顿结束 开始下一次循环
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 300).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert!(events.contains(&AnimationEvent::Loop(group0, 1)));
+        assert!(events.contains(&AnimationEvent::IterationStart(group0, 1)));
+        assert_eq!(animation_context_amount.group_infos.get(group0).unwrap().running_time_ms, 1100.);
+        assert_eq!(state(&animation_context_amount, group0).1, 1);
+
+        // 最后一次循环后 不停顿
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1400).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        assert!(animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group0)));
+        // 有随机停顿的动画组 结束较晚
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        assert!(animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group1)));
+
+        // 量化模式 停顿结束时 立即以新的运行时间计算
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+        animation_context_amount.set_group_iteration_delay(group, Some(IterationDelay::new(500., EIterationHold::LastFrame))).unwrap();
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert_eq!(state(&animation_context_amount, group), (1., 0));
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 450).unwrap();
+        let (amount, looped_count) = state(&animation_context_amount, group);
+        assert_eq!(looped_count, 1);
+        assert!(amount < 0.1);
+
+        // 绝对时钟回退时 停顿时间由时刻重新得到
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+        animation_context_amount.set_group_iteration_delay(group, Some(IterationDelay::new(500., EIterationHold::LastFrame))).unwrap();
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::Positive(Some(3)), 60, AnimationAmountCalc::default(), 0., EFillMode::NONE).unwrap();
+        let running = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>| {
+            let info = ctx.group_infos.get(group).unwrap();
+            (info.running_time_ms, info.looped_count)
+        };
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 0.).unwrap();
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 3.2).unwrap();
+        assert_eq!(running(&animation_context_amount), (2200., 2));
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 1.2).unwrap();
+        assert_eq!(running(&animation_context_amount), (1000., 0));
+        assert_eq!(state(&animation_context_amount, group), (1., 0));
+        type_animation_ctx_mgr.anime_at(&mut animation_context_amount, 2.0).unwrap();
+        assert_eq!(running(&animation_context_amount), (1500., 1));
+    }
+
+    #[test]
+    fn test_iteration_range() {
+        use pi_animation::{base::ETimeStepMode, animation_listener::AnimationEvent};
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group0 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group0, target.anime_target_id()).unwrap();
+        let group1 = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V1 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group1, target.anime_target_id()).unwrap();
+
+        // 播放 2.5 次
+        animation_context_amount.set_group_iteration_range(group0, 0., Some(2.5)).unwrap();
+        animation_context_amount.start_complete(group0, 1.0, ELoopMode::Positive(None), 60, AnimationAmountCalc::default(), 0., EFillMode::FORWARDS).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group0), Ok(Some(2500.)));
+        // 从第一次循环的 30% 处开始 播放 1 次
+        animation_context_amount.set_group_iteration_range(group1, 0.3, None).unwrap();
+        animation_context_amount.start_complete(group1, 1.0, ELoopMode::Positive(Some(1)), 60, AnimationAmountCalc::default(), 0., EFillMode::FORWARDS).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group1), Ok(Some(1000.)));
+
+        let amount = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>, group| {
+            ctx.group_infos.get(group).unwrap().amount_in_second
+        };
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 100).unwrap();
+        assert!((amount(&animation_context_amount, group1) - 0.4).abs() < 0.0001);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 900).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert!(events.contains(&AnimationEvent::Loop(group0, 1)));
+        assert!(events.contains(&AnimationEvent::Loop(group1, 1)));
+        // 结束位置不在循环边界上 FORWARDS 保持结束位置
+        assert!(events.contains(&AnimationEvent::End(group1)));
+        assert!((amount(&animation_context_amount, group1) - 0.3).abs() < 0.0001);
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        let events = animation_context_amount.drain_events().collect::<Vec<_>>();
+        assert!(events.contains(&AnimationEvent::Loop(group0, 2)));
+        assert!(!events.contains(&AnimationEvent::End(group0)));
+        assert_eq!(animation_context_amount.remaining_time_ms(group0), Ok(Some(500.)));
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 600).unwrap();
+        assert!(animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group0)));
+        assert!((amount(&animation_context_amount, group0) - 0.5).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_custom_loop_mode() {
+        use pi_animation::{base::{ETimeStepMode, TimeMS}, loop_mode::{TLoopMode, IterationRange}, animation_listener::AnimationEvent};
+
+        /// 转向时带缓动的往复循环
+        struct EasedPingPong;
+        impl TLoopMode for EasedPingPong {
+            fn amount(&self, once_time: TimeMS, time: TimeMS) -> (f32, u32) {
+                let index = (time / once_time).floor() as u32;
+                let t = (time / once_time - index as TimeMS) as f32;
+                let eased = t * t * (3. - 2. * t);
+                (if self.is_positive_pass(index) { eased } else { 1. - eased }, index / 2)
+            }
+            fn loop_passes(&self) -> u32 {
+                2
+            }
+            fn is_positive_pass(&self, index: u32) -> bool {
+                index & 1 == 0
+            }
+        }
+
+        // ELoopMode 指定的播放次数 按自定义循环模式换算为循环次数
+        assert_eq!(IterationRange::default().resolve(ELoopMode::Positive(Some(2)), &EasedPingPong).count, Some(1.));
+
+        let mut type_animation_ctx_mgr = TypeAnimationContextMgr::default();
+        let mut animation_context_amount = AnimationContextAmount::<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>::default(AnimationGroupManagerDefault::default());
+        animation_context_amount.time_step_mode(ETimeStepMode::Continuous);
+
+        let target = Target0::default(type_animation_ctx_mgr.allocat_target_id());
+        let frame_count = 30 as FrameIndex;
+        let curve = crate::AssetCurve::<f32>(Arc::new(FrameCurve::curve_easing(0.0f32, 100.0f32, frame_count, frame_count, EEasingMode::None)));
+        let group = animation_context_amount.create_animation_group();
+        let animation = type_animation_ctx_mgr.f32_ctx.create_animation(Target0AnimatableAttrSet::V2 as IDAnimatableAttr, curve.clone());
+        animation_context_amount.add_target_animation_notype(animation, group, target.anime_target_id()).unwrap();
+
+        animation_context_amount.set_group_loop_calc(group, Some(Arc::new(EasedPingPong))).unwrap();
+        animation_context_amount.start_complete(group, 1.0, ELoopMode::PositivePly(Some(1)), 60, AnimationAmountCalc::default(), 0., EFillMode::FORWARDS).unwrap();
+        assert_eq!(animation_context_amount.remaining_time_ms(group), Ok(Some(2000.)));
+
+        let state = |ctx: &AnimationContextAmount<DefaultKey, AnimationGroupManagerDefault<DefaultKey>>| {
+            let info = ctx.group_infos.get(group).unwrap();
+            (info.amount_in_second, info.positive_pass)
+        };
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 250).unwrap();
+        assert_eq!(state(&animation_context_amount), (0.15625, true));
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 1000).unwrap();
+        assert_eq!(state(&animation_context_amount), (0.84375, false));
+
+        type_animation_ctx_mgr.anime(&mut animation_context_amount, 750).unwrap();
+        assert!(animation_context_amount.drain_events().any(|event| event == AnimationEvent::End(group)));
+        assert_eq!(state(&animation_context_amount).0, 0.);
+    }
+
     #[bench]
     fn test_peformance(b: &mut Bencher) {
         let curve_range = 100_000;